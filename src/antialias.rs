@@ -0,0 +1,187 @@
+use crate::blend::{blend_pixel, BlendMode};
+
+/// Blends `color` into the pixel at (x, y) with its alpha scaled by `coverage` (0..=1).
+pub fn plot(
+    canvas: &mut [u8],
+    width: usize,
+    x: isize,
+    y: isize,
+    color: (u8, u8, u8, u8),
+    coverage: f64,
+) {
+    if x < 0 || y < 0 || x as usize >= width || coverage <= 0. {
+        return;
+    }
+
+    let i = y as usize * (width * 4) + (x as usize * 4);
+
+    blend_pixel(canvas, i, color, coverage, BlendMode::SourceOver);
+}
+
+/// Xiaolin Wu's line: every step covers two pixels weighted by their distance to the line.
+pub fn draw_line_aa(
+    canvas: &mut [u8],
+    (x0, y0): (f64, f64),
+    (x1, y1): (f64, f64),
+    width: usize,
+    color: (u8, u8, u8, u8),
+) {
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    let (x0, y0, x1, y1) = if steep {
+        (y0, x0, y1, x1)
+    } else {
+        (x0, y0, x1, y1)
+    };
+    let (x0, y0, x1, y1) = if x0 > x1 {
+        (x1, y1, x0, y0)
+    } else {
+        (x0, y0, x1, y1)
+    };
+
+    let gradient = if x1 == x0 { 1. } else { (y1 - y0) / (x1 - x0) };
+
+    let mut plot_pair = |x: isize, y: f64, coverage: f64| {
+        let (top, fraction) = (y.floor() as isize, y - y.floor());
+        for (y, c) in [(top, 1. - fraction), (top + 1, fraction)] {
+            if steep {
+                plot(canvas, width, y, x, color, c * coverage);
+            } else {
+                plot(canvas, width, x, y, color, c * coverage);
+            }
+        }
+    };
+
+    let start = x0.round();
+    let end = x1.round();
+    let mut y = y0 + gradient * (start - x0);
+
+    for x in start as isize..=end as isize {
+        let coverage = if x == start as isize {
+            1. - (x0 + 0.5 - start)
+        } else if x == end as isize {
+            x1 + 0.5 - end
+        } else {
+            1.
+        };
+
+        plot_pair(x, y, coverage.clamp(0., 1.));
+        y += gradient;
+    }
+}
+
+/// Fills a triangle weighting edge pixels by how much of them lies inside.
+pub fn draw_triangle_filled_aa(
+    canvas: &mut [u8],
+    a: (f64, f64),
+    b: (f64, f64),
+    c: (f64, f64),
+    width: usize,
+    color: (u8, u8, u8, u8),
+) {
+    let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    if area.abs() < f64::EPSILON || area.is_nan() {
+        return;
+    }
+    let (b, c) = if area < 0. { (c, b) } else { (b, c) };
+
+    let edge_distance = |p: (f64, f64), q: (f64, f64), (x, y): (f64, f64)| {
+        let (ex, ey) = (q.0 - p.0, q.1 - p.1);
+        (ex * (y - p.1) - ey * (x - p.0)) / (ex * ex + ey * ey).sqrt()
+    };
+
+    let x_min = a.0.min(b.0).min(c.0).floor() as isize - 1;
+    let x_max = a.0.max(b.0).max(c.0).ceil() as isize + 1;
+    let y_min = a.1.min(b.1).min(c.1).floor() as isize - 1;
+    let y_max = a.1.max(b.1).max(c.1).ceil() as isize + 1;
+
+    for y in y_min.max(0)..=y_max {
+        for x in x_min.max(0)..=x_max.min(width as isize - 1) {
+            let center = (x as f64 + 0.5, y as f64 + 0.5);
+            let distance = edge_distance(a, b, center)
+                .min(edge_distance(b, c, center))
+                .min(edge_distance(c, a, center));
+
+            plot(canvas, width, x, y, color, (distance + 0.5).clamp(0., 1.));
+        }
+    }
+}
+
+/// Coverage of the pixel centered at (`dx`, `dy`) from the ellipse center by an ellipse
+/// with the given radii.
+pub fn ellipse_coverage(dx: f64, dy: f64, rx: f64, ry: f64) -> f64 {
+    if rx <= 0. || ry <= 0. {
+        return 0.;
+    }
+
+    let f = (dx / rx).powi(2) + (dy / ry).powi(2) - 1.;
+    let gradient = 2. * ((dx / (rx * rx)).powi(2) + (dy / (ry * ry)).powi(2)).sqrt();
+    if gradient == 0. {
+        return 1.;
+    }
+
+    (0.5 - f / gradient).clamp(0., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        arrow::{draw_arrow_bordered, draw_arrow_filled},
+        circle::draw_ellipse_filled,
+        line::draw_line_thick,
+        path::draw_path,
+        rectangle::draw_rect_bordered,
+    };
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    #[test]
+    fn antialiased_coverage_adds_up() {
+        let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+        draw_line_aa(
+            &mut canvas,
+            (10., 10.),
+            (90., 50.),
+            WIDTH,
+            (255, 255, 255, 255),
+        );
+        for x in 12..88 {
+            let column: usize = (0..HEIGHT)
+                .map(|y| canvas[y * WIDTH * 4 + x * 4 + 3] as usize)
+                .sum();
+            assert!(column.abs_diff(255) <= 2, "column {x} covers {column}");
+        }
+
+        let area: f64 = (-30..30)
+            .flat_map(|x| (-30..30).map(move |y| (x as f64 + 0.5, y as f64 + 0.5)))
+            .map(|(dx, dy)| ellipse_coverage(dx, dy, 20., 10.))
+            .sum();
+        assert!((area - std::f64::consts::PI * 200.).abs() < 6.);
+    }
+
+    #[test]
+    fn crisp_shapes_use_only_opaque_pixels() {
+        let (a, b, w, t) = ((30, 20), (150, 70), WIDTH, 5);
+        let color = (255, 0, 0, 255);
+        for kind in 0..6 {
+            let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+            match kind {
+                0 => draw_arrow_bordered(&mut canvas, a.0, a.1, b.0, b.1, w, t, false, color),
+                1 => draw_arrow_filled(&mut canvas, a.0, a.1, b.0, b.1, w, t, false, color),
+                2 => draw_line_thick(&mut canvas, a.0, a.1, b.0, b.1, w, t, false, color),
+                3 => draw_rect_bordered(&mut canvas, a.0, a.1, b.0, b.1, w, t, false, color),
+                4 => draw_ellipse_filled(&mut canvas, a.0, a.1, b.0, b.1, w, false, color),
+                _ => draw_path(&mut canvas, &[a, (60, 80), b], w, t, false, color),
+            }
+
+            assert!(canvas.chunks(4).any(|pixel| pixel[3] == 255), "{kind}");
+            assert!(
+                canvas
+                    .chunks(4)
+                    .all(|pixel| pixel[3] == 0 || pixel[3] == 255),
+                "{kind}"
+            );
+        }
+    }
+}
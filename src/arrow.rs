@@ -1,5 +1,11 @@
-use crate::{line::draw_line, triangle::draw_triangle_filled};
+use crate::{
+    antialias::draw_triangle_filled_aa,
+    blend::BlendMode,
+    stroke::{draw_polyline, Cap, Join, Stroke},
+    triangle::draw_triangle_filled,
+};
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw_arrow_bordered(
     canvas: &mut [u8],
     x0: usize,
@@ -7,6 +13,8 @@ pub fn draw_arrow_bordered(
     x1: usize,
     y1: usize,
     width: usize,
+    thickness: usize,
+    antialiasing: bool,
     color: (u8, u8, u8, u8),
 ) {
     if (x1 as isize - x0 as isize).abs() < 3 && (y1 as isize - y0 as isize).abs() < 3 {
@@ -15,135 +23,41 @@ pub fn draw_arrow_bordered(
 
     let (arrow_head_a, arrow_head_b) =
         calculate_arrow_head((x0 as f64, y0 as f64), (x1 as f64, y1 as f64));
+    let tip = (x1 as f64, y1 as f64);
+    let stroke = Stroke {
+        width: thickness.max(1) as f64,
+        cap: Cap::Round,
+        join: Join::Miter,
+    };
 
-    if !arrow_head_a.0.is_nan() && !arrow_head_a.1.is_nan() {
-        draw_line(
-            canvas,
-            arrow_head_a.0 as usize,
-            arrow_head_a.1 as usize,
-            x1,
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_a.0 as usize + 1,
-            arrow_head_a.1 as usize,
-            x1 + 1,
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            (arrow_head_a.0 as usize).saturating_sub(1),
-            arrow_head_a.1 as usize,
-            x1.saturating_sub(1),
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_a.0 as usize,
-            (arrow_head_a.1 as usize).saturating_sub(1),
-            x1,
-            y1.saturating_sub(1),
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_a.0 as usize,
-            arrow_head_a.1 as usize + 1,
-            x1,
-            y1 + 1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_a.0 as usize,
-            (arrow_head_a.1 as usize).saturating_sub(1),
-            x1,
-            y1.saturating_sub(1),
-            width,
-            color,
-        );
-    }
-
-    if !arrow_head_b.0.is_nan() && !arrow_head_b.1.is_nan() {
-        draw_line(
-            canvas,
-            arrow_head_b.0 as usize,
-            arrow_head_b.1 as usize,
-            x1,
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_b.0 as usize + 1,
-            arrow_head_b.1 as usize,
-            x1 + 1,
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            (arrow_head_b.0 as usize).saturating_sub(1),
-            arrow_head_b.1 as usize,
-            x1.saturating_sub(1),
-            y1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_b.0 as usize,
-            arrow_head_b.1 as usize + 1,
-            x1,
-            y1 + 1,
-            width,
-            color,
-        );
-        draw_line(
-            canvas,
-            arrow_head_b.0 as usize,
-            (arrow_head_b.1 as usize).saturating_sub(1),
-            x1,
-            y1.saturating_sub(1),
-            width,
-            color,
-        );
-    }
-
-    draw_line(canvas, x0, y0, x1, y1, width, color);
-    draw_line(canvas, x0, y0 + 1, x1, y1 + 1, width, color);
-    draw_line(
+    draw_polyline(
         canvas,
-        x0,
-        y0.saturating_sub(1),
-        x1,
-        y1.saturating_sub(1),
         width,
+        &[(x0 as f64, y0 as f64), tip],
+        false,
+        stroke,
+        antialiasing,
         color,
+        BlendMode::SourceOver,
     );
-    draw_line(canvas, x0 + 1, y0, x1 + 1, y1, width, color);
-    draw_line(
+
+    let head: Vec<_> = [arrow_head_a, tip, arrow_head_b]
+        .into_iter()
+        .filter(|(x, y)| !x.is_nan() && !y.is_nan())
+        .collect();
+    draw_polyline(
         canvas,
-        x0.saturating_sub(1),
-        y0,
-        x1.saturating_sub(1),
-        y1,
         width,
+        &head,
+        false,
+        stroke,
+        antialiasing,
         color,
+        BlendMode::SourceOver,
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw_arrow_filled(
     canvas: &mut [u8],
     x0: usize,
@@ -151,21 +65,48 @@ pub fn draw_arrow_filled(
     x1: usize,
     y1: usize,
     width: usize,
+    thickness: usize,
+    antialiasing: bool,
     color: (u8, u8, u8, u8),
 ) {
-    draw_arrow_bordered(canvas, x0, y0, x1, y1, width, color);
-
-    let (arrow_head_a, arrow_head_b) =
-        calculate_arrow_head((x0 as f64, y0 as f64), (x1 as f64, y1 as f64));
-
-    draw_triangle_filled(
+    draw_arrow_bordered(
         canvas,
-        (x1, y1),
-        (arrow_head_a.0 as usize, arrow_head_a.1 as usize),
-        (arrow_head_b.0 as usize, arrow_head_b.1 as usize),
+        x0,
+        y0,
+        x1,
+        y1,
         width,
+        thickness,
+        antialiasing,
         color,
     );
+
+    let (arrow_head_a, arrow_head_b) =
+        calculate_arrow_head((x0 as f64, y0 as f64), (x1 as f64, y1 as f64));
+    let finite = |(x, y): (f64, f64)| x.is_finite() && y.is_finite();
+    if !finite(arrow_head_a) || !finite(arrow_head_b) {
+        return;
+    }
+
+    if antialiasing {
+        draw_triangle_filled_aa(
+            canvas,
+            (x1 as f64, y1 as f64),
+            arrow_head_a,
+            arrow_head_b,
+            width,
+            color,
+        );
+    } else {
+        draw_triangle_filled(
+            canvas,
+            (x1, y1),
+            (arrow_head_a.0 as usize, arrow_head_a.1 as usize),
+            (arrow_head_b.0 as usize, arrow_head_b.1 as usize),
+            width,
+            color,
+        );
+    }
 }
 
 pub fn calculate_arrow_head(start: (f64, f64), end: (f64, f64)) -> ((f64, f64), (f64, f64)) {
@@ -194,3 +135,35 @@ pub fn calculate_arrow_head(start: (f64, f64), end: (f64, f64)) -> ((f64, f64),
 
     ((x3a, y3a), (x3b, y3b))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    #[test]
+    fn vertical_filled_arrow_stays_on_its_line() {
+        for antialiasing in [false, true] {
+            let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+            draw_arrow_filled(
+                &mut canvas,
+                100,
+                10,
+                100,
+                90,
+                WIDTH,
+                5,
+                antialiasing,
+                (255, 0, 0, 255),
+            );
+
+            for (i, pixel) in canvas.chunks(4).enumerate() {
+                let x = i % WIDTH;
+                assert!(pixel[3] == 0 || x.abs_diff(100) <= 5, "{x} {antialiasing}");
+            }
+            assert_eq!(canvas[(50 * WIDTH + 100) * 4 + 3], 255);
+        }
+    }
+}
@@ -1,16 +1,99 @@
-#[allow(dead_code)]
-pub fn blend(bg: (u8, u8, u8, u8), fg: (u8, u8, u8, u8)) -> (u8, u8, u8, u8) {
-    let a = fg.3 as f32 / bg.3 as f32;
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlendMode {
+    #[default]
+    SourceOver,
+    Multiply,
+    Screen,
+}
+
+/// Composites a straight (not premultiplied) alpha `fg` color over `bg` as described in
+/// https://www.w3.org/TR/compositing-1/#generalformula
+pub fn blend(bg: (u8, u8, u8, u8), fg: (u8, u8, u8, u8), mode: BlendMode) -> (u8, u8, u8, u8) {
+    let alpha_s = fg.3 as f64 / 255.;
+    let alpha_b = bg.3 as f64 / 255.;
+    let alpha_o = alpha_s + alpha_b * (1. - alpha_s);
+    if alpha_o == 0. {
+        return (0, 0, 0, 0);
+    }
+
+    let channel = |cs: u8, cb: u8| {
+        let (cs, cb) = (cs as f64 / 255., cb as f64 / 255.);
+        let mixed = match mode {
+            BlendMode::SourceOver => cs,
+            BlendMode::Multiply => cs * cb,
+            BlendMode::Screen => cs + cb - cs * cb,
+        };
+        let cs = (1. - alpha_b) * cs + alpha_b * mixed;
+        let co = (alpha_s * cs + alpha_b * cb * (1. - alpha_s)) / alpha_o;
 
-    let result_red = (fg.0 as f32 * a) + (bg.0 as f32 * (1. - a));
-    let result_green = (fg.1 as f32 * a) + (bg.1 as f32 * (1. - a));
-    let result_blue = (fg.2 as f32 * a) + (bg.2 as f32 * (1. - a));
-    let result_alpha = fg.3 as f32 + (bg.3 as f32 * (1. - fg.3 as f32 / bg.3 as f32));
+        (co * 255.).round() as u8
+    };
 
     (
-        result_red as u8,
-        result_green as u8,
-        result_blue as u8,
-        result_alpha as u8,
+        channel(fg.0, bg.0),
+        channel(fg.1, bg.1),
+        channel(fg.2, bg.2),
+        (alpha_o * 255.).round() as u8,
     )
 }
+
+/// Composites `color` with its alpha scaled by `coverage` (0..=1) into the canvas pixel
+/// which starts at byte `i`. Pixels outside of the canvas are skipped.
+pub fn blend_pixel(
+    canvas: &mut [u8],
+    i: usize,
+    color: (u8, u8, u8, u8),
+    coverage: f64,
+    mode: BlendMode,
+) {
+    if coverage <= 0. || canvas.get(i + 3).is_none() {
+        return;
+    }
+
+    let fg = (
+        color.0,
+        color.1,
+        color.2,
+        (color.3 as f64 * coverage.min(1.)).round() as u8,
+    );
+    let bg = (canvas[i], canvas[i + 1], canvas[i + 2], canvas[i + 3]);
+    let (r, g, b, a) = blend(bg, fg, mode);
+
+    canvas[i] = r;
+    canvas[i + 1] = g;
+    canvas[i + 2] = b;
+    canvas[i + 3] = a;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_modes_follow_compositing_spec() {
+        let half_red = (255, 0, 0, 128);
+        assert_eq!(
+            blend((0, 0, 255, 255), half_red, BlendMode::SourceOver),
+            (128, 0, 127, 255)
+        );
+        assert_eq!(
+            blend((0, 0, 0, 0), half_red, BlendMode::SourceOver),
+            half_red
+        );
+        assert_eq!(
+            blend((0, 0, 0, 0), (0, 0, 0, 0), BlendMode::SourceOver),
+            (0, 0, 0, 0)
+        );
+
+        assert_eq!(
+            blend((200, 100, 50, 255), (128, 255, 0, 255), BlendMode::Multiply),
+            (100, 100, 0, 255)
+        );
+        assert_eq!(
+            blend((100, 100, 100, 255), (128, 0, 255, 255), BlendMode::Screen),
+            (178, 100, 255, 255)
+        );
+        // over a transparent background the blend mode has nothing to mix with
+        assert_eq!(blend((0, 0, 0, 0), half_red, BlendMode::Multiply), half_red);
+    }
+}
@@ -1,5 +1,63 @@
+use std::collections::HashMap;
+
 use fastblur::gaussian_blur;
 
+use crate::Pos2;
+
+pub const DEFAULT_RADIUS: usize = 5;
+pub const MAX_RADIUS: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlurKernel {
+    Gaussian,
+    Box,
+    /// Horizontal smear, like a camera moving sideways.
+    Motion,
+}
+
+impl BlurKernel {
+    pub fn next(self) -> Self {
+        match self {
+            BlurKernel::Gaussian => BlurKernel::Box,
+            BlurKernel::Box => BlurKernel::Motion,
+            BlurKernel::Motion => BlurKernel::Gaussian,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    p0: Pos2,
+    p1: Pos2,
+    radius: usize,
+    kernel: BlurKernel,
+}
+
+struct CacheEntry {
+    input: Vec<u8>,
+    output: Vec<u8>,
+    used: bool,
+}
+
+/// Blurred areas of the previous redraw. An entry is reused as long as the pixels under it
+/// didn't change.
+#[derive(Default)]
+pub struct BlurCache {
+    entries: HashMap<CacheKey, CacheEntry>,
+}
+
+impl BlurCache {
+    /// Drops the areas which weren't drawn since the previous sweep.
+    pub fn sweep(&mut self) {
+        self.entries.retain(|_, entry| entry.used);
+        for entry in self.entries.values_mut() {
+            entry.used = false;
+        }
+    }
+}
+
+/// Blurs the rectangle with the given kernel. The alpha channel is left as it is.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_rect_blurred(
     canvas: &mut [u8],
     x0: usize,
@@ -7,41 +65,185 @@ pub fn draw_rect_blurred(
     x1: usize,
     y1: usize,
     width: usize,
+    radius: usize,
+    kernel: BlurKernel,
+    cache: &mut BlurCache,
 ) {
     let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
     let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
-
-    let blur_width = x1 - x0;
-    let blur_height = y1 - y0;
-    let radius = 5;
-    if blur_width < radius || blur_height < radius {
+    let height = canvas.len() / (width * 4).max(1);
+    let (x1, y1) = (x1.min(width), y1.min(height));
+    if x0 >= x1 || y0 >= y1 {
         return;
     }
 
-    let mut bluring_area = vec![];
-    for hh in y0..y1 {
-        for ww in x0..x1 {
-            if (hh >= y0 && hh <= y1) && (ww >= x0 && ww <= x1) {
-                let arr: [u8; 3] = [
-                    canvas[hh * (width * 4) + (ww * 4)],
-                    canvas[hh * (width * 4) + (ww * 4) + 1],
-                    canvas[hh * (width * 4) + (ww * 4) + 2],
-                ];
-                bluring_area.push(arr);
+    let row = |hh: usize| hh * (width * 4) + x0 * 4..hh * (width * 4) + x1 * 4;
+    let input: Vec<u8> = (y0..y1).flat_map(|hh| canvas[row(hh)].to_vec()).collect();
+
+    let key = CacheKey {
+        p0: (x0, y0),
+        p1: (x1, y1),
+        radius,
+        kernel,
+    };
+    let entry = match cache.entries.get_mut(&key) {
+        Some(entry) if entry.input == input => entry,
+        _ => {
+            let output = blur(&input, x1 - x0, y1 - y0, radius, kernel);
+            cache.entries.insert(
+                key,
+                CacheEntry {
+                    input,
+                    output,
+                    used: false,
+                },
+            );
+            cache.entries.get_mut(&key).unwrap()
+        }
+    };
+    entry.used = true;
+
+    let area_row = (x1 - x0) * 4;
+    for (i, hh) in (y0..y1).enumerate() {
+        canvas[row(hh)].copy_from_slice(&entry.output[i * area_row..(i + 1) * area_row]);
+    }
+}
+
+/// Blurs the RGB channels of an RGBA area. The radius is at least 1, a Gaussian blur is limited
+/// to the area and too narrow areas get a box blur, so no area stays readable.
+fn blur(area: &[u8], width: usize, height: usize, radius: usize, kernel: BlurKernel) -> Vec<u8> {
+    let mut rgb: Vec<[u8; 3]> = area.chunks(4).map(|p| [p[0], p[1], p[2]]).collect();
+    let radius = radius.max(1);
+
+    match kernel {
+        // fastblur needs a radius smaller than both sides of the area
+        BlurKernel::Gaussian if width > 1 && height > 1 => {
+            let radius = radius.min(width.min(height) - 1);
+            gaussian_blur(&mut rgb, width, height, radius as f32);
+        }
+        BlurKernel::Motion if width > 1 => {
+            rgb = box_blur(&rgb, width, height, radius, (1, 0));
+        }
+        // the box blur, which the others fall back to on areas a single pixel wide or high
+        BlurKernel::Box | BlurKernel::Gaussian | BlurKernel::Motion => {
+            rgb = box_blur(&rgb, width, height, radius, (1, 0));
+            rgb = box_blur(&rgb, width, height, radius, (0, 1));
+        }
+    }
+
+    area.chunks(4)
+        .zip(rgb)
+        .flat_map(|(p, [r, g, b])| [r, g, b, p[3]])
+        .collect()
+}
+
+/// One pass of a box blur along `direction`, averaging a running window of `2 * radius + 1`
+/// pixels which is clamped at the area's edges.
+fn box_blur(
+    rgb: &[[u8; 3]],
+    width: usize,
+    height: usize,
+    radius: usize,
+    direction: (usize, usize),
+) -> Vec<[u8; 3]> {
+    let (lines, length) = if direction.0 == 1 {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    let index = |line: usize, i: usize| {
+        if direction.0 == 1 {
+            line * width + i
+        } else {
+            i * width + line
+        }
+    };
+
+    let mut blurred = vec![[0; 3]; rgb.len()];
+    for line in 0..lines {
+        let mut sum = [0usize; 3];
+        let mut count = 0;
+        for i in 0..radius.min(length - 1) + 1 {
+            let p = rgb[index(line, i)];
+            (0..3).for_each(|c| sum[c] += p[c] as usize);
+            count += 1;
+        }
+
+        for i in 0..length {
+            blurred[index(line, i)] = sum.map(|s| (s / count) as u8);
+
+            if i + radius + 1 < length {
+                let p = rgb[index(line, i + radius + 1)];
+                (0..3).for_each(|c| sum[c] += p[c] as usize);
+                count += 1;
+            }
+            if i >= radius {
+                let p = rgb[index(line, i - radius)];
+                (0..3).for_each(|c| sum[c] -= p[c] as usize);
+                count -= 1;
             }
         }
     }
 
-    gaussian_blur(&mut bluring_area, blur_width, blur_height, radius as f32);
+    blurred
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for hh in 0..blur_height {
-        for ww in 0..blur_width {
-            let [r, g, b] = bluring_area[hh * blur_width + ww];
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    #[test]
+    fn blur_kernels_spread_differently() {
+        let blurred = |kernel| {
+            let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+            canvas[50 * WIDTH * 4 + 100 * 4..50 * WIDTH * 4 + 100 * 4 + 4].fill(200);
+            let mut cache = BlurCache::default();
+            draw_rect_blurred(&mut canvas, 80, 30, 120, 70, WIDTH, 2, kernel, &mut cache);
+
+            move |x: usize, y: usize| {
+                canvas[y * WIDTH * 4 + x * 4..y * WIDTH * 4 + x * 4 + 4].to_vec()
+            }
+        };
 
-            canvas[(hh + y0) * (width * 4) + ((ww + x0) * 4)] = r;
-            canvas[(hh + y0) * (width * 4) + ((ww + x0) * 4) + 1] = g;
-            canvas[(hh + y0) * (width * 4) + ((ww + x0) * 4) + 2] = b;
-            canvas[(hh + y0) * (width * 4) + ((ww + x0) * 4) + 3] = 255;
+        let motion = blurred(BlurKernel::Motion);
+        assert_eq!(motion(102, 50), [40, 40, 40, 0]);
+        assert_eq!(motion(100, 51), [0, 0, 0, 0]);
+
+        let boxed = blurred(BlurKernel::Box);
+        assert_eq!(boxed(102, 52), [8, 8, 8, 0]);
+        assert_eq!(boxed(103, 50), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn small_areas_are_blurred_at_any_radius() {
+        let mut original = vec![0; WIDTH * HEIGHT * 4];
+        for (i, pixel) in original.chunks_mut(4).enumerate() {
+            pixel.copy_from_slice(&[(i % 7 * 40) as u8, (i % 3 * 100) as u8, 0, 255]);
+        }
+
+        for radius in [0, 5, MAX_RADIUS] {
+            for kernel in [BlurKernel::Gaussian, BlurKernel::Box, BlurKernel::Motion] {
+                for (p0, p1) in [((40, 40), (60, 50)), ((40, 40), (41, 60))] {
+                    let mut canvas = original.clone();
+                    let mut cache = BlurCache::default();
+                    let (x0, y0, x1, y1) = (p0.0, p0.1, p1.0, p1.1);
+                    draw_rect_blurred(
+                        &mut canvas,
+                        x0,
+                        y0,
+                        x1,
+                        y1,
+                        WIDTH,
+                        radius,
+                        kernel,
+                        &mut cache,
+                    );
+                    assert!(canvas != original, "{kernel:?} at radius {radius}");
+                }
+            }
         }
     }
 }
@@ -1,32 +1,130 @@
-#[allow(dead_code)]
+use crate::{
+    antialias::{ellipse_coverage, plot},
+    blend::{blend_pixel, BlendMode},
+};
+
+/// Draws a filled circle, which keeps its shape when it's partially off the canvas.
 pub fn draw_circle_filled(
     canvas: &mut [u8],
     x: usize,
     y: usize,
     r: usize,
     width: usize,
+    antialiasing: bool,
     color: (u8, u8, u8, u8),
 ) {
-    let red = color.0;
-    let green = color.1;
-    let blue = color.2;
-    let alpha = color.3;
-
-    let x0 = x - r;
-    let x1 = x + r;
-    let y0 = y - r;
-    let y1 = y + r;
-
-    for hh in y0..y1 {
-        for ww in x0..x1 {
-            let dy = (y as isize - hh as isize).unsigned_abs();
-            let dx = (x as isize - ww as isize).unsigned_abs();
-
-            if dx * dx + dy * dy <= r * r {
-                canvas[hh * (width * 4) + (ww * 4)] = red;
-                canvas[hh * (width * 4) + (ww * 4) + 1] = green;
-                canvas[hh * (width * 4) + (ww * 4) + 2] = blue;
-                canvas[hh * (width * 4) + (ww * 4) + 3] = alpha;
+    let radius = r as f64;
+
+    let height = canvas.len() / (width * 4).max(1);
+
+    for hh in y.saturating_sub(r)..(y + r).min(height) {
+        for ww in x.saturating_sub(r)..(x + r).min(width) {
+            let (dx, dy) = (ww as f64 + 0.5 - x as f64, hh as f64 + 0.5 - y as f64);
+
+            if antialiasing {
+                let coverage = ellipse_coverage(dx, dy, radius, radius);
+                plot(canvas, width, ww as isize, hh as isize, color, coverage);
+            } else if dx * dx + dy * dy <= radius * radius {
+                blend_pixel(
+                    canvas,
+                    hh * (width * 4) + (ww * 4),
+                    color,
+                    1.,
+                    BlendMode::SourceOver,
+                );
+            }
+        }
+    }
+}
+
+/// Draws an ellipse inscribed into the given bounding box.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_ellipse_filled(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    draw_ellipse(canvas, x0, y0, x1, y1, width, None, antialiasing, color);
+}
+
+/// Draws a `thickness` pixels wide outline of an ellipse inscribed into the given bounding box.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_ellipse_bordered(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    thickness: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    draw_ellipse(
+        canvas,
+        x0,
+        y0,
+        x1,
+        y1,
+        width,
+        Some(thickness),
+        antialiasing,
+        color,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_ellipse(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    thickness: Option<usize>,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
+    let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
+    if x0 == x1 || y0 == y1 {
+        return;
+    }
+
+    let (cx, cy) = ((x0 + x1) as f64 / 2., (y0 + y1) as f64 / 2.);
+    let (rx, ry) = ((x1 - x0) as f64 / 2., (y1 - y0) as f64 / 2.);
+    let t = thickness.map_or(f64::INFINITY, |t| t as f64);
+
+    let height = canvas.len() / (width * 4).max(1);
+
+    for hh in y0..y1.min(height) {
+        for ww in x0..x1.min(width) {
+            let (dx, dy) = (ww as f64 + 0.5 - cx, hh as f64 + 0.5 - cy);
+
+            if antialiasing {
+                let coverage = ellipse_coverage(dx, dy, rx, ry)
+                    * (1. - ellipse_coverage(dx, dy, rx - t, ry - t));
+                plot(canvas, width, ww as isize, hh as isize, color, coverage);
+                continue;
+            }
+
+            let inside = |rx: f64, ry: f64| {
+                rx > 0. && ry > 0. && (dx / rx).powi(2) + (dy / ry).powi(2) <= 1.
+            };
+
+            if inside(rx, ry) && !inside(rx - t, ry - t) {
+                blend_pixel(
+                    canvas,
+                    hh * (width * 4) + (ww * 4),
+                    color,
+                    1.,
+                    BlendMode::SourceOver,
+                );
             }
         }
     }
@@ -0,0 +1,442 @@
+use crate::BorderColor;
+
+/// Parses a color written as `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, a CSS color name,
+/// `rgb()`, `rgba()`, `hsl()`, `hsla()` or as comma separated `r,g,b` and optional `a` values
+/// between 0 and 255.
+pub fn parse_color(s: &str) -> Result<BorderColor, String> {
+    let color = s.trim().to_ascii_lowercase();
+
+    if let Some(hex) = color.strip_prefix('#') {
+        parse_hex(hex)
+    } else if let Some((function, arguments)) = color
+        .strip_suffix(')')
+        .and_then(|color| color.split_once('('))
+    {
+        parse_function(function.trim(), arguments)
+    } else if color.contains(',') {
+        parse_components(&color)
+    } else {
+        parse_name(&color)
+    }
+}
+
+fn parse_hex(hex: &str) -> Result<BorderColor, String> {
+    if let Some(c) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("`{c}` isn't a hex digit"));
+    }
+
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).unwrap_or_default() as u8)
+        .collect();
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|d| d[0] * 16 + d[1]).collect(),
+        count => {
+            return Err(format!(
+                "expected 3, 4, 6 or 8 hex digits after `#`, got {count}"
+            ))
+        }
+    };
+
+    Ok(BorderColor {
+        r: channels[0],
+        g: channels[1],
+        b: channels[2],
+        a: channels.get(3).copied().unwrap_or(255),
+    })
+}
+
+/// `rgb()` and `hsl()` with either comma or space separated values, the alpha may follow a `/`.
+fn parse_function(function: &str, arguments: &str) -> Result<BorderColor, String> {
+    let values: Vec<_> = arguments
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .collect();
+    let (channels, alpha) = match values.as_slice() {
+        [a, b, c] => ([*a, *b, *c], None),
+        [a, b, c, alpha] => ([*a, *b, *c], Some(*alpha)),
+        _ => {
+            return Err(format!(
+                "{function}() takes 3 values and an optional alpha, got {}",
+                values.len()
+            ))
+        }
+    };
+    let a = alpha.map_or(Ok(255), parse_alpha)?;
+
+    match function {
+        "rgb" | "rgba" => {
+            let [r, g, b] = [
+                parse_channel(channels[0])?,
+                parse_channel(channels[1])?,
+                parse_channel(channels[2])?,
+            ];
+            Ok(BorderColor { r, g, b, a })
+        }
+        "hsl" | "hsla" => {
+            let hue = parse_hue(channels[0])?;
+            let saturation = parse_percentage(channels[1])?;
+            let lightness = parse_percentage(channels[2])?;
+            let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+            Ok(BorderColor { r, g, b, a })
+        }
+        _ => Err(format!(
+            "unknown function `{function}()`, expected rgb(), rgba(), hsl() or hsla()"
+        )),
+    }
+}
+
+fn parse_components(color: &str) -> Result<BorderColor, String> {
+    let values = color
+        .split(',')
+        .map(|value| {
+            value
+                .trim()
+                .parse::<u8>()
+                .map_err(|_| format!("`{}` isn't a number between 0 and 255", value.trim()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match values[..] {
+        [r, g, b] => Ok(BorderColor::rgb(r, g, b)),
+        [r, g, b, a] => Ok(BorderColor { r, g, b, a }),
+        _ => Err(format!(
+            "expected 3 or 4 comma separated values r,g,b,a, got {}",
+            values.len()
+        )),
+    }
+}
+
+fn parse_name(name: &str) -> Result<BorderColor, String> {
+    if name == "transparent" {
+        return Ok(BorderColor {
+            r: 0,
+            g: 0,
+            b: 0,
+            a: 0,
+        });
+    }
+
+    if let Some((_, rgb)) = NAMED_COLORS.iter().find(|(known, _)| *known == name) {
+        let [_, r, g, b] = rgb.to_be_bytes();
+        return Ok(BorderColor::rgb(r, g, b));
+    }
+
+    let closest = NAMED_COLORS
+        .iter()
+        .map(|(known, _)| (edit_distance(name, known), *known))
+        .min()
+        .filter(|(distance, _)| *distance <= 2);
+    match closest {
+        Some((_, known)) => Err(format!("unknown color name, did you mean `{known}`?")),
+        None => {
+            Err("expected #rrggbb, a CSS color name, rgb(), hsl() or r,g,b,a values".to_string())
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| format!("`{value}` isn't a number"))
+}
+
+/// A channel between 0 and 255, or a percentage of it.
+fn parse_channel(value: &str) -> Result<u8, String> {
+    let channel = match value.strip_suffix('%') {
+        Some(percentage) => parse_number(percentage)? / 100. * 255.,
+        None => parse_number(value)?,
+    };
+
+    if (0. ..=255.).contains(&channel) {
+        Ok(channel.round() as u8)
+    } else {
+        Err(format!("`{value}` is out of range 0-255"))
+    }
+}
+
+/// An alpha between 0 and 1, or a percentage.
+fn parse_alpha(value: &str) -> Result<u8, String> {
+    let alpha = match value.strip_suffix('%') {
+        Some(percentage) => parse_number(percentage)? / 100.,
+        None => parse_number(value)?,
+    };
+
+    if (0. ..=1.).contains(&alpha) {
+        Ok((alpha * 255.).round() as u8)
+    } else {
+        Err(format!("alpha `{value}` is out of range 0-1"))
+    }
+}
+
+/// A percentage as a fraction, the `%` may be left out.
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    let percentage = parse_number(value.strip_suffix('%').unwrap_or(value))?;
+
+    if (0. ..=100.).contains(&percentage) {
+        Ok(percentage / 100.)
+    } else {
+        Err(format!("`{value}` is out of range 0%-100%"))
+    }
+}
+
+/// A hue in degrees, written as a plain number or with a `deg` or `turn` unit.
+fn parse_hue(value: &str) -> Result<f64, String> {
+    let degrees = if let Some(turns) = value.strip_suffix("turn") {
+        parse_number(turns)? * 360.
+    } else {
+        parse_number(value.strip_suffix("deg").unwrap_or(value))?
+    };
+
+    Ok(degrees.rem_euclid(360.))
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let sector = hue / 60.;
+    let x = chroma * (1. - (sector % 2. - 1.).abs());
+    let (r, g, b) = match sector as usize {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let m = lightness - chroma / 2.;
+    let channel = |c: f64| ((c + m) * 255.).round() as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
+/// Number of single character insertions, deletions and substitutions turning `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// CSS named colors.
+const NAMED_COLORS: &[(&str, u32)] = &[
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn colors_parse_in_every_notation() {
+        let color = |s: &str| s.parse::<BorderColor>();
+        let rgba = |r, g, b, a| Ok(BorderColor { r, g, b, a });
+
+        assert_eq!(color("255,0,255,255"), rgba(255, 0, 255, 255));
+        assert_eq!(color(" 10, 20, 30 "), rgba(10, 20, 30, 255));
+        assert_eq!(color("#F0a"), rgba(255, 0, 170, 255));
+        assert_eq!(color("#f0a8"), rgba(255, 0, 170, 136));
+        assert_eq!(color("#123456"), rgba(0x12, 0x34, 0x56, 255));
+        assert_eq!(color("#12345678"), rgba(0x12, 0x34, 0x56, 0x78));
+        assert_eq!(color("RebeccaPurple"), rgba(102, 51, 153, 255));
+        assert_eq!(color("transparent"), rgba(0, 0, 0, 0));
+        assert_eq!(color("rgb(255, 128, 0)"), rgba(255, 128, 0, 255));
+        assert_eq!(color("rgba(255, 128, 0, 0.5)"), rgba(255, 128, 0, 128));
+        assert_eq!(color("rgb(100% 0% 50% / 25%)"), rgba(255, 0, 128, 64));
+        assert_eq!(color("hsl(0, 100%, 50%)"), rgba(255, 0, 0, 255));
+        assert_eq!(color("hsl(120deg 100% 25%)"), rgba(0, 128, 0, 255));
+        assert_eq!(color("hsla(0.5turn, 100%, 50%, 1)"), rgba(0, 255, 255, 255));
+        assert_eq!(color("hsl(-60, 100%, 50%)"), color("magenta"));
+
+        let error = |s: &str| color(s).unwrap_err();
+        assert_eq!(
+            error("#12345"),
+            "expected 3, 4, 6 or 8 hex digits after `#`, got 5"
+        );
+        assert_eq!(error("#ggg"), "`g` isn't a hex digit");
+        assert_eq!(error("grren"), "unknown color name, did you mean `green`?");
+        assert_eq!(error("rgb(300, 0, 0)"), "`300` is out of range 0-255");
+        assert_eq!(error("rgba(0, 0, 0, 2)"), "alpha `2` is out of range 0-1");
+        assert_eq!(
+            error("hsl(0, 100%)"),
+            "hsl() takes 3 values and an optional alpha, got 2"
+        );
+        assert_eq!(
+            error("cmyk(0, 0, 0, 0)"),
+            "unknown function `cmyk()`, expected rgb(), rgba(), hsl() or hsla()"
+        );
+        assert_eq!(
+            error("1,2"),
+            "expected 3 or 4 comma separated values r,g,b,a, got 2"
+        );
+        assert_eq!(error("1,2,256"), "`256` isn't a number between 0 and 255");
+
+        let config =
+            Config::parse("border_color = \"navy\"\npalette = [\"#f00\", \"hsl(240, 100%, 50%)\"]")
+                .unwrap();
+        assert_eq!(config.border_color, BorderColor::rgb(0, 0, 128));
+        assert_eq!(
+            config.palette,
+            vec![BorderColor::rgb(255, 0, 0), BorderColor::rgb(0, 0, 255)]
+        );
+    }
+}
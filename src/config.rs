@@ -0,0 +1,136 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    blur::{DEFAULT_RADIUS, MAX_RADIUS},
+    keymap::Keymap,
+    redact::{DEFAULT_BLOCK_SIZE, MAX_BLOCK_SIZE, MIN_BLOCK_SIZE},
+    style::{DEFAULT_PALETTE, DEFAULT_STROKE_WIDTH, MAX_STROKE_WIDTH},
+    text::DEFAULT_FONT_SIZE,
+    BorderColor,
+};
+
+const DEFAULT_DIM_LEVEL: u8 = 100;
+const DEFAULT_BORDER_WIDTH: usize = 2;
+
+/// Settings read from the config file. Every one of them may be left out of the file, command
+/// line flags override them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Color of the selection border and of the shapes drawn first.
+    pub border_color: BorderColor,
+    /// Index of the only captured screen, unset captures the whole desktop.
+    pub screen: Option<usize>,
+    /// Save directory.
+    pub dir: Option<PathBuf>,
+    /// Save to clipboard instead of `dir`.
+    pub clipboard: bool,
+    /// Draw shapes with anti-aliased edges instead of crisp pixels.
+    pub antialiasing: bool,
+    /// Size in pixels of the blocks of new pixelated rectangles.
+    pub pixelate_block_size: usize,
+    /// Radius in pixels of new blurred rectangles.
+    pub blur_radius: usize,
+    /// Colors switched between by the color hotkey, the number keys pick the first nine directly.
+    pub palette: Vec<BorderColor>,
+    /// Stroke width of the shapes drawn first, the stroke width hotkeys change it.
+    pub stroke_width: usize,
+    /// Size in pixels of typed text.
+    pub font_size: f32,
+    /// Alpha of the area outside of the selection, 255 doesn't dim it at all.
+    pub dim_level: u8,
+    /// Width of the selection border.
+    pub border_width: usize,
+    /// Chords of every action, the file only needs the actions bound to other than default keys.
+    pub keys: Keymap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            border_color: BorderColor::default(),
+            screen: None,
+            dir: None,
+            clipboard: false,
+            antialiasing: true,
+            pixelate_block_size: DEFAULT_BLOCK_SIZE,
+            blur_radius: DEFAULT_RADIUS,
+            palette: DEFAULT_PALETTE.to_vec(),
+            stroke_width: DEFAULT_STROKE_WIDTH,
+            font_size: DEFAULT_FONT_SIZE,
+            dim_level: DEFAULT_DIM_LEVEL,
+            border_width: DEFAULT_BORDER_WIDTH,
+            keys: Keymap::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file at `path`. A missing file means the defaults.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                Self::parse(&content).map_err(|e| format!("Bad config {}: {e}", path.display()))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(format!("Can't read config {}: {err}", path.display())),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(content).map_err(|e| e.to_string())?;
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Checks the values which parse but can't be used, also after command line flags are
+    /// applied.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_STROKE_WIDTH).contains(&self.stroke_width) {
+            return Err(format!(
+                "stroke_width has to be between 1 and {MAX_STROKE_WIDTH}."
+            ));
+        }
+        if !(1..=MAX_STROKE_WIDTH).contains(&self.border_width) {
+            return Err(format!(
+                "border_width has to be between 1 and {MAX_STROKE_WIDTH}."
+            ));
+        }
+        if !(1..=MAX_RADIUS).contains(&self.blur_radius) {
+            return Err(format!("blur_radius has to be between 1 and {MAX_RADIUS}."));
+        }
+        if !(MIN_BLOCK_SIZE..=MAX_BLOCK_SIZE).contains(&self.pixelate_block_size) {
+            return Err(format!(
+                "pixelate_block_size has to be between {MIN_BLOCK_SIZE} and {MAX_BLOCK_SIZE}."
+            ));
+        }
+        if !(self.font_size.is_finite() && self.font_size > 0.) {
+            return Err("font_size has to be a positive number.".to_string());
+        }
+        if self.palette.is_empty() {
+            return Err("palette needs at least one color.".to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("config is always representable in TOML")
+    }
+}
+
+/// `$XDG_CONFIG_HOME/birdy/config.toml`, with `~/.config` used when the variable isn't set.
+pub fn config_path() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(base.join("birdy").join("config.toml"))
+}
@@ -0,0 +1,204 @@
+use image::{
+    imageops::{self, FilterType},
+    Rgba, RgbaImage,
+};
+use screenshots::display_info::DisplayInfo;
+use winit::monitor::MonitorHandle;
+
+use crate::Pos2;
+
+/// Rectangle on the virtual desktop, in the physical pixels winit reports for monitors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Area {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Area {
+    fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    fn overlaps(&self, other: &Area) -> bool {
+        self.x < other.right()
+            && other.x < self.right()
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+impl From<&MonitorHandle> for Area {
+    fn from(monitor: &MonitorHandle) -> Self {
+        let (position, size) = (monitor.position(), monitor.size());
+        Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+}
+
+/// Image of a screen together with the area of its monitor.
+pub struct ScreenCapture {
+    pub area: Area,
+    pub image: RgbaImage,
+}
+
+/// Physical area of a display, which reports its geometry in logical coordinates.
+pub fn display_area(display: &DisplayInfo) -> Area {
+    let scaled = |length: f64| (length * display.scale_factor as f64).round();
+    Area {
+        x: scaled(display.x as f64) as i32,
+        y: scaled(display.y as f64) as i32,
+        width: scaled(display.width as f64) as u32,
+        height: scaled(display.height as f64) as u32,
+    }
+}
+
+/// Index of the monitor closest to `area` in position and size.
+pub fn nearest_monitor(area: Area, monitors: &[Area]) -> Option<usize> {
+    (0..monitors.len()).min_by_key(|&i| {
+        let monitor = monitors[i];
+        area.x.abs_diff(monitor.x)
+            + area.y.abs_diff(monitor.y)
+            + area.width.abs_diff(monitor.width)
+            + area.height.abs_diff(monitor.height)
+    })
+}
+
+/// Moves overlapping areas apart. Wayland and macOS report logical monitor positions next to
+/// physical sizes, so a monitor scaled above one covers part of its right or bottom neighbour;
+/// the neighbour is moved just past it. Layouts without overlaps are kept as they are.
+pub fn layout(areas: &mut [Area]) {
+    let mut order: Vec<_> = (0..areas.len()).collect();
+    order.sort_by_key(|&i| (areas[i].x, areas[i].y));
+
+    for (placed, &i) in order.iter().enumerate() {
+        while let Some(other) = order[..placed]
+            .iter()
+            .map(|&j| areas[j])
+            .find(|other| other.overlaps(&areas[i]))
+        {
+            let area = &mut areas[i];
+            if area.x - other.x >= area.y - other.y {
+                area.x = other.right();
+            } else {
+                area.y = other.bottom();
+            }
+        }
+    }
+}
+
+/// Smallest area covering every one of `areas`.
+pub fn monitor_bounds(areas: impl IntoIterator<Item = Area>) -> Option<Area> {
+    areas
+        .into_iter()
+        .map(|area| (area.x, area.y, area.right(), area.bottom()))
+        .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))
+        .map(|(left, top, right, bottom)| Area {
+            x: left,
+            y: top,
+            width: (right - left) as u32,
+            height: (bottom - top) as u32,
+        })
+}
+
+/// Places every capture in its area of one image covering all of them, areas no monitor covers
+/// are black. Captures keep their pixels, only one which doesn't have the size of its monitor is
+/// resampled to it.
+pub fn compose(captures: &[ScreenCapture]) -> RgbaImage {
+    let Some(bounds) = monitor_bounds(captures.iter().map(|capture| capture.area)) else {
+        return RgbaImage::new(0, 0);
+    };
+
+    let mut desktop = RgbaImage::from_pixel(bounds.width, bounds.height, Rgba([0, 0, 0, 255]));
+    for ScreenCapture { area, image } in captures {
+        let (x, y) = ((area.x - bounds.x).into(), (area.y - bounds.y).into());
+        if image.dimensions() == (area.width, area.height) {
+            imageops::replace(&mut desktop, image, x, y);
+        } else {
+            let image = imageops::resize(image, area.width, area.height, FilterType::Triangle);
+            imageops::replace(&mut desktop, &image, x, y);
+        }
+    }
+
+    desktop
+}
+
+/// Copies the part of `desktop` starting at `offset` which fills `frame`, a canvas `width` pixels
+/// wide.
+pub fn copy_view(
+    desktop: &[u8],
+    desktop_width: usize,
+    offset: Pos2,
+    frame: &mut [u8],
+    width: usize,
+) {
+    let row = width * 4;
+    for (y, line) in frame.chunks_exact_mut(row).enumerate() {
+        let start = ((offset.1 + y) * desktop_width + offset.0) * 4;
+        if let Some(source) = desktop.get(start..start + row) {
+            line.copy_from_slice(source);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_and_monitor_bounds_agree_on_mixed_scales() {
+        // As on Wayland: a 2x screen reports its logical position next to its physical size and
+        // overlaps the 1x screen on its right.
+        let mut areas = [
+            Area {
+                x: 0,
+                y: 0,
+                width: 120,
+                height: 100,
+            },
+            Area {
+                x: 60,
+                y: 0,
+                width: 80,
+                height: 50,
+            },
+        ];
+        layout(&mut areas);
+        let bounds = monitor_bounds(areas).unwrap();
+        assert_eq!(areas[1].x, 120);
+        assert_eq!((bounds.width, bounds.height), (200, 100));
+
+        let gradient = |area: Area, blue| {
+            RgbaImage::from_fn(area.width, area.height, |x, y| {
+                Rgba([x as u8, y as u8, blue, 255])
+            })
+        };
+        let captures = areas.map(|area| ScreenCapture {
+            area,
+            image: gradient(area, area.x as u8),
+        });
+        let desktop = compose(&captures);
+        assert_eq!(desktop.dimensions(), (bounds.width, bounds.height));
+        for ScreenCapture { area, image } in &captures {
+            let view = imageops::crop_imm(
+                &desktop,
+                area.x as u32,
+                area.y as u32,
+                area.width,
+                area.height,
+            );
+            assert_eq!(view.to_image(), *image);
+        }
+        // below the 2x screen, which is half as high
+        assert_eq!(*desktop.get_pixel(150, 70), Rgba([0, 0, 0, 255]));
+    }
+}
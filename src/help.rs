@@ -0,0 +1,121 @@
+use crate::{
+    rect::Rect,
+    rectangle::draw_rect_filled,
+    text::{draw_text, init_layout, text_width},
+};
+
+const FONT_SIZE: f32 = 15.;
+const PADDING: usize = 16;
+const BACKGROUND: (u8, u8, u8, u8) = (0, 0, 0, 210);
+const KEYS_COLOR: (u8, u8, u8, u8) = (255, 210, 0, 255);
+const TEXT_COLOR: (u8, u8, u8, u8) = (255, 255, 255, 255);
+
+/// Draws a sheet centered on `screen` listing the keys of every line next to its description,
+/// which is wrapped to fit the screen.
+pub fn draw_help(canvas: &mut [u8], width: usize, screen: Rect, lines: &[(String, &str)]) {
+    // mouse gestures have long descriptions of their keys, so they are placed on their own line
+    let column = lines
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .filter(|length| *length <= 20)
+        .max()
+        .unwrap_or(0)
+        + 2;
+    let max_width = screen.width().saturating_sub(2 * PADDING);
+
+    let (keys, descriptions): (Vec<_>, Vec<_>) = lines
+        .iter()
+        .map(|(keys, description)| {
+            let wrapped = wrap(description, column, max_width);
+            if keys.chars().count() < column {
+                let breaks = "\n".repeat(wrapped.len() - 1);
+                (format!("{keys}{breaks}"), wrapped.join("\n"))
+            } else {
+                let keys = wrap(keys, 0, max_width);
+                (
+                    format!("{}{}", keys.join("\n"), "\n".repeat(wrapped.len())),
+                    format!("{}{}", "\n".repeat(keys.len()), wrapped.join("\n")),
+                )
+            }
+        })
+        .unzip();
+    let (keys, descriptions) = (keys.join("\n"), descriptions.join("\n"));
+
+    let (layout, _) = init_layout(FONT_SIZE, &descriptions, 0., 0.);
+    let text_height = layout.height() as usize;
+    let size = (
+        text_width(&layout).max(text_width(&init_layout(FONT_SIZE, &keys, 0., 0.).0)) + 2 * PADDING,
+        text_height + 2 * PADDING,
+    );
+    let (x, y) = (
+        screen.p0().0 + screen.width().saturating_sub(size.0) / 2,
+        screen.p0().1 + screen.height().saturating_sub(size.1) / 2,
+    );
+
+    draw_rect_filled(canvas, x, y, x + size.0, y + size.1, width, BACKGROUND);
+    for (content, color) in [(&keys, KEYS_COLOR), (&descriptions, TEXT_COLOR)] {
+        let (layout, fonts) = init_layout(
+            FONT_SIZE,
+            content,
+            (x + PADDING) as f32,
+            (y + PADDING) as f32,
+        );
+        draw_text(canvas, width, color, &layout, &fonts);
+    }
+}
+
+/// Breaks `text` between words into lines indented by `indent` spaces which are at most
+/// `max_width` pixels wide, a single word too long for that gets a line of its own.
+fn wrap(text: &str, indent: usize, max_width: usize) -> Vec<String> {
+    let fits = |line: &str| text_width(&init_layout(FONT_SIZE, line, 0., 0.).0) <= max_width;
+
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            format!("{:indent$}{word}", "")
+        } else {
+            format!("{line} {word}")
+        };
+        if line.is_empty() || fits(&candidate) {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(
+                &mut line,
+                format!("{:indent$}{word}", ""),
+            ));
+        }
+    }
+    lines.push(line);
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::Keymap;
+
+    #[test]
+    fn descriptions_wrap_to_the_canvas() {
+        let max_width = 400;
+        let mut wrapped = 0;
+        for (keys, description) in Keymap::default().help_lines() {
+            assert!(wrap(&keys, 0, max_width)
+                .iter()
+                .all(|line| { text_width(&init_layout(FONT_SIZE, line, 0., 0.).0) <= max_width }));
+            let lines = wrap(description, 4, max_width);
+            wrapped += (lines.len() > 1) as usize;
+            for line in &lines {
+                assert!(line.starts_with("    "));
+                assert!(text_width(&init_layout(FONT_SIZE, line, 0., 0.).0) <= max_width);
+            }
+            assert_eq!(
+                lines.join(" ").split_whitespace().collect::<Vec<_>>(),
+                description.split_whitespace().collect::<Vec<_>>()
+            );
+        }
+        // the fill toggle and secure redaction descriptions at least
+        assert!(wrapped >= 2);
+    }
+}
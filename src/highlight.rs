@@ -0,0 +1,99 @@
+use crate::{
+    blend::BlendMode,
+    rectangle::draw_rect_filled_blended,
+    stroke::{draw_polyline, Cap, Join, Stroke},
+};
+
+/// How many times a highlighter stroke is wider than the stroke width of other shapes.
+pub const WIDTH_SCALE: usize = 6;
+/// Alpha of the highlighter color at full opacity, so it stays translucent.
+const ALPHA: usize = 160;
+
+/// Draws a wide flat-capped marker stroke. Multiplying keeps dark content underneath readable,
+/// see [`blend_mode`] for dark areas.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_highlight_stroke(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    thickness: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    let reach = thickness * WIDTH_SCALE / 2;
+    let mode = blend_mode(
+        canvas,
+        width,
+        (
+            x0.min(x1).saturating_sub(reach),
+            y0.min(y1).saturating_sub(reach),
+        ),
+        (x0.max(x1) + reach, y0.max(y1) + reach),
+    );
+    draw_polyline(
+        canvas,
+        width,
+        &[(x0 as f64, y0 as f64), (x1 as f64, y1 as f64)],
+        false,
+        Stroke {
+            width: (thickness * WIDTH_SCALE) as f64,
+            cap: Cap::Butt,
+            join: Join::Miter,
+        },
+        antialiasing,
+        translucent(color),
+        mode,
+    );
+}
+
+/// Highlights the whole rectangle between the given corners.
+pub fn draw_highlight_filled(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    color: (u8, u8, u8, u8),
+) {
+    let mode = blend_mode(
+        canvas,
+        width,
+        (x0.min(x1), y0.min(y1)),
+        (x0.max(x1), y0.max(y1)),
+    );
+    draw_rect_filled_blended(canvas, x0, y0, x1, y1, width, translucent(color), mode);
+}
+
+/// Multiplying barely shows on dark areas, as in dark themes, so a highlight over an area which
+/// is dark on average screens it instead and lightens the background.
+fn blend_mode(canvas: &[u8], width: usize, p0: (usize, usize), p1: (usize, usize)) -> BlendMode {
+    let height = canvas.len() / (width * 4).max(1);
+    let (x1, y1) = (p1.0.min(width), p1.1.min(height));
+
+    let mut luma = 0;
+    let mut count = 0;
+    for y in p0.1..y1 {
+        for x in p0.0..x1 {
+            let i = y * (width * 4) + x * 4;
+            luma += (canvas[i] as usize * 299
+                + canvas[i + 1] as usize * 587
+                + canvas[i + 2] as usize * 114)
+                / 1000;
+            count += 1;
+        }
+    }
+
+    if count > 0 && luma / count < 128 {
+        BlendMode::Screen
+    } else {
+        BlendMode::Multiply
+    }
+}
+
+fn translucent((r, g, b, a): (u8, u8, u8, u8)) -> (u8, u8, u8, u8) {
+    (r, g, b, (a as usize * ALPHA / 255) as u8)
+}
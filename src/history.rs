@@ -0,0 +1,55 @@
+use crate::{keycode_to_text::Cursor, rect::Rect, DrawnItem};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Edit {
+    Push(DrawnItem),
+    Remove {
+        index: usize,
+        item: DrawnItem,
+    },
+    Replace {
+        index: usize,
+        before: DrawnItem,
+        after: DrawnItem,
+    },
+    Selection {
+        before: Rect,
+        after: Rect,
+    },
+    Text {
+        before: (Cursor, String),
+        after: (Cursor, String),
+    },
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+}
+
+impl History {
+    pub fn record(&mut self, edit: Edit) {
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<Edit> {
+        let edit = self.undo.pop()?;
+        self.redo.push(edit.clone());
+
+        Some(edit)
+    }
+
+    pub fn redo(&mut self) -> Option<Edit> {
+        let edit = self.redo.pop()?;
+        self.undo.push(edit.clone());
+
+        Some(edit)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
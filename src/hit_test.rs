@@ -0,0 +1,211 @@
+use crate::{
+    highlight::WIDTH_SCALE,
+    step::RADIUS,
+    text::{init_layout, text_bounds},
+    DrawnItem, Pos2,
+};
+
+pub const HANDLE_SIZE: usize = 10;
+const TOLERANCE: f64 = 8.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grab {
+    Move,
+    Handle(usize),
+}
+
+/// Points of an item which can be dragged separately: line endpoints or rectangle corners.
+pub fn handles(item: &DrawnItem) -> Vec<Pos2> {
+    match item {
+        DrawnItem::Arrow(p0, p1, _) | DrawnItem::Line(p0, p1, _) => vec![*p0, *p1],
+        DrawnItem::Highlight(p0, p1, style) if !style.fill => vec![*p0, *p1],
+        DrawnItem::Rect(p0, p1, _)
+        | DrawnItem::Highlight(p0, p1, _)
+        | DrawnItem::Ellipse(p0, p1, _)
+        | DrawnItem::Redact(p0, p1, _) => vec![*p0, (p1.0, p0.1), *p1, (p0.0, p1.1)],
+        DrawnItem::Step(..) | DrawnItem::Path(..) | DrawnItem::Text(..) => vec![],
+    }
+}
+
+pub fn hit_handle(item: &DrawnItem, pos: Pos2) -> Option<usize> {
+    handles(item).iter().position(|handle| {
+        pos.0.abs_diff(handle.0) <= HANDLE_SIZE && pos.1.abs_diff(handle.1) <= HANDLE_SIZE
+    })
+}
+
+/// Whether `pos` is on the item. Texts are measured at `font_size`.
+pub fn hit_item(item: &DrawnItem, pos: Pos2, font_size: f32) -> bool {
+    match item {
+        DrawnItem::Arrow(p0, p1, style) | DrawnItem::Line(p0, p1, style) => {
+            distance_to_segment(pos, *p0, *p1) <= TOLERANCE + style.stroke_width as f64 / 2.
+        }
+        DrawnItem::Step(center, _) => distance_to_segment(pos, *center, *center) <= RADIUS as f64,
+        DrawnItem::Path(points, style) => {
+            let tolerance = TOLERANCE + style.stroke_width as f64 / 2.;
+            match points.as_slice() {
+                [point] => distance_to_segment(pos, *point, *point) <= tolerance,
+                points => points
+                    .windows(2)
+                    .any(|pair| distance_to_segment(pos, pair[0], pair[1]) <= tolerance),
+            }
+        }
+        DrawnItem::Highlight(p0, p1, style) if !style.fill => {
+            let half = (style.stroke_width * WIDTH_SCALE) as f64 / 2.;
+            distance_to_segment(pos, *p0, *p1) <= half
+        }
+        DrawnItem::Rect(p0, p1, style) if !style.fill => {
+            let tolerance = TOLERANCE as isize + style.stroke_width as isize;
+            let outer = inflate(*p0, *p1, tolerance);
+            let inner = inflate(*p0, *p1, -tolerance);
+
+            contains(outer, pos) && !contains(inner, pos)
+        }
+        DrawnItem::Rect(p0, p1, _)
+        | DrawnItem::Highlight(p0, p1, _)
+        | DrawnItem::Redact(p0, p1, _) => contains(normalize(*p0, *p1), pos),
+        DrawnItem::Ellipse(p0, p1, style) => {
+            let distance = ellipse_distance(*p0, *p1, pos);
+            let tolerance = TOLERANCE + style.stroke_width as f64;
+
+            if style.fill {
+                distance <= TOLERANCE
+            } else {
+                distance.abs() <= tolerance
+            }
+        }
+        DrawnItem::Text((_, content, (x, y)), _) => {
+            let (layout, _) = init_layout(font_size, content, *x as f32, *y as f32);
+
+            text_bounds(&layout).is_some_and(|bounds| contains(bounds, pos))
+        }
+    }
+}
+
+/// Returns the item shifted by the given offset, keeping it inside the positive quadrant.
+pub fn translate(item: &DrawnItem, dx: isize, dy: isize) -> DrawnItem {
+    let shift = |(x, y): Pos2| (x.saturating_add_signed(dx), y.saturating_add_signed(dy));
+
+    match item {
+        DrawnItem::Arrow(p0, p1, style) => DrawnItem::Arrow(shift(*p0), shift(*p1), *style),
+        DrawnItem::Line(p0, p1, style) => DrawnItem::Line(shift(*p0), shift(*p1), *style),
+        DrawnItem::Rect(p0, p1, style) => DrawnItem::Rect(shift(*p0), shift(*p1), *style),
+        DrawnItem::Ellipse(p0, p1, style) => DrawnItem::Ellipse(shift(*p0), shift(*p1), *style),
+        DrawnItem::Step(center, style) => DrawnItem::Step(shift(*center), *style),
+        DrawnItem::Path(points, style) => {
+            DrawnItem::Path(points.iter().copied().map(shift).collect(), *style)
+        }
+        DrawnItem::Highlight(p0, p1, style) => DrawnItem::Highlight(shift(*p0), shift(*p1), *style),
+        DrawnItem::Redact(p0, p1, redaction) => {
+            DrawnItem::Redact(shift(*p0), shift(*p1), *redaction)
+        }
+        DrawnItem::Text((cursor, content, p0), style) => {
+            DrawnItem::Text((*cursor, content.clone(), shift(*p0)), *style)
+        }
+    }
+}
+
+/// Returns the item with one of its [`handles`] moved to `pos`.
+pub fn move_handle(item: &DrawnItem, handle: usize, pos: Pos2) -> DrawnItem {
+    let endpoints = |p0: Pos2, p1: Pos2| match handle {
+        0 => (pos, p1),
+        _ => (p0, pos),
+    };
+    let corners = |p0: Pos2, p1: Pos2| match handle {
+        0 => (pos, p1),
+        1 => ((p0.0, pos.1), (pos.0, p1.1)),
+        2 => (p0, pos),
+        _ => ((pos.0, p0.1), (p1.0, pos.1)),
+    };
+
+    match item {
+        DrawnItem::Arrow(p0, p1, style) => {
+            let (p0, p1) = endpoints(*p0, *p1);
+            DrawnItem::Arrow(p0, p1, *style)
+        }
+        DrawnItem::Line(p0, p1, style) => {
+            let (p0, p1) = endpoints(*p0, *p1);
+            DrawnItem::Line(p0, p1, *style)
+        }
+        DrawnItem::Rect(p0, p1, style) => {
+            let (p0, p1) = corners(*p0, *p1);
+            DrawnItem::Rect(p0, p1, *style)
+        }
+        DrawnItem::Ellipse(p0, p1, style) => {
+            let (p0, p1) = corners(*p0, *p1);
+            DrawnItem::Ellipse(p0, p1, *style)
+        }
+        DrawnItem::Highlight(p0, p1, style) => {
+            let (p0, p1) = if style.fill {
+                corners(*p0, *p1)
+            } else {
+                endpoints(*p0, *p1)
+            };
+            DrawnItem::Highlight(p0, p1, *style)
+        }
+        DrawnItem::Redact(p0, p1, redaction) => {
+            let (p0, p1) = corners(*p0, *p1);
+            DrawnItem::Redact(p0, p1, *redaction)
+        }
+        DrawnItem::Step(..) | DrawnItem::Path(..) | DrawnItem::Text(..) => item.clone(),
+    }
+}
+
+fn distance_to_segment(p: Pos2, a: Pos2, b: Pos2) -> f64 {
+    let (px, py) = (p.0 as f64, p.1 as f64);
+    let (ax, ay) = (a.0 as f64, a.1 as f64);
+    let (bx, by) = (b.0 as f64, b.1 as f64);
+
+    let length = (bx - ax).powi(2) + (by - ay).powi(2);
+    let t = if length == 0. {
+        0.
+    } else {
+        (((px - ax) * (bx - ax) + (py - ay) * (by - ay)) / length).clamp(0., 1.)
+    };
+
+    ((px - (ax + t * (bx - ax))).powi(2) + (py - (ay + t * (by - ay))).powi(2)).sqrt()
+}
+
+/// Approximate signed distance from an ellipse inscribed into the given box, negative inside.
+fn ellipse_distance(p0: Pos2, p1: Pos2, pos: Pos2) -> f64 {
+    let (p0, p1) = normalize(p0, p1);
+    let (cx, cy) = ((p0.0 + p1.0) as f64 / 2., (p0.1 + p1.1) as f64 / 2.);
+    let (rx, ry) = (
+        ((p1.0 - p0.0) as f64 / 2.).max(1.),
+        ((p1.1 - p0.1) as f64 / 2.).max(1.),
+    );
+    let (dx, dy) = (pos.0 as f64 - cx, pos.1 as f64 - cy);
+
+    let scaled = ((dx / rx).powi(2) + (dy / ry).powi(2)).sqrt();
+    if scaled == 0. {
+        return -rx.min(ry);
+    }
+
+    // distance along the ray from the center to the point
+    (dx.powi(2) + dy.powi(2)).sqrt() * (1. - 1. / scaled)
+}
+
+fn normalize(p0: Pos2, p1: Pos2) -> (Pos2, Pos2) {
+    (
+        (p0.0.min(p1.0), p0.1.min(p1.1)),
+        (p0.0.max(p1.0), p0.1.max(p1.1)),
+    )
+}
+
+fn inflate(p0: Pos2, p1: Pos2, by: isize) -> (Pos2, Pos2) {
+    let (p0, p1) = normalize(p0, p1);
+
+    (
+        (
+            p0.0.saturating_add_signed(-by),
+            p0.1.saturating_add_signed(-by),
+        ),
+        (
+            p1.0.saturating_add_signed(by),
+            p1.1.saturating_add_signed(by),
+        ),
+    )
+}
+
+fn contains((p0, p1): (Pos2, Pos2), pos: Pos2) -> bool {
+    pos.0 >= p0.0 && pos.0 <= p1.0 && pos.1 >= p0.1 && pos.1 <= p1.1
+}
@@ -0,0 +1,128 @@
+use crate::{
+    rect::Rect,
+    rectangle::{draw_rect_bordered, draw_rect_filled},
+    text::{draw_text, init_layout, text_width},
+    Pos2,
+};
+
+const FONT_SIZE: f32 = 14.;
+const HEIGHT: usize = 22;
+const PADDING: usize = 6;
+const SWATCH: usize = 12;
+/// Room between the palette swatches, the active one is outlined inside of it.
+const PALETTE_GAP: usize = 4;
+/// Palette colors reachable through the number keys.
+const PALETTE_KEYS: usize = 9;
+/// Distance between the selection and the HUD.
+const GAP: usize = 6;
+const BACKGROUND: (u8, u8, u8, u8) = (0, 0, 0, 190);
+
+/// Describes the selected `area` and the cursor position, followed by the active tool.
+pub fn hud_text(area: Rect, cursor: Option<Pos2>, tool: &str, stroke_width: usize) -> String {
+    let mut text = format!(
+        "{}×{} at {},{}",
+        area.width(),
+        area.height(),
+        area.p0().0,
+        area.p0().1
+    );
+    if let Some((x, y)) = cursor {
+        text += &format!("  cursor {x},{y}");
+    }
+
+    text + &format!("  {tool} {stroke_width}px")
+}
+
+/// Top left corner of a HUD of the given size: below the selection, above it when there is no
+/// room below, or inside of it when the selection covers the whole height.
+pub fn hud_origin(selection: Rect, (width, height): Pos2, size: Pos2) -> Pos2 {
+    let y = if selection.p1().1 + GAP + size.1 <= height {
+        selection.p1().1 + GAP
+    } else if selection.p0().1 >= GAP + size.1 {
+        selection.p0().1 - GAP - size.1
+    } else {
+        selection.p0().1 + GAP
+    };
+
+    (selection.p0().0.min(width.saturating_sub(size.0)), y)
+}
+
+/// Draws `text` on a dark strip next to the selection, preceded by a swatch of `color` and
+/// followed by the colors of the number keys, with the `active` one outlined.
+pub fn draw_hud(
+    canvas: &mut [u8],
+    width: usize,
+    selection: Rect,
+    text: &str,
+    color: (u8, u8, u8, u8),
+    palette: &[(u8, u8, u8, u8)],
+    active: Option<usize>,
+) {
+    let height = canvas.len() / (width * 4).max(1);
+    let (layout, _) = init_layout(FONT_SIZE, text, 0., 0.);
+    let palette = &palette[..palette.len().min(PALETTE_KEYS)];
+    let text_end = PADDING * 3 + SWATCH + text_width(&layout);
+    let size = (text_end + palette.len() * (SWATCH + PALETTE_GAP), HEIGHT);
+    let (x, y) = hud_origin(selection, (width, height), size);
+
+    draw_rect_filled(canvas, x, y, x + size.0, y + size.1, width, BACKGROUND);
+
+    let swatch = (x + PADDING, y + (HEIGHT - SWATCH) / 2);
+    draw_rect_filled(
+        canvas,
+        swatch.0,
+        swatch.1,
+        swatch.0 + SWATCH,
+        swatch.1 + SWATCH,
+        width,
+        color,
+    );
+    draw_rect_bordered(
+        canvas,
+        swatch.0,
+        swatch.1,
+        swatch.0 + SWATCH,
+        swatch.1 + SWATCH,
+        width,
+        1,
+        false,
+        (255, 255, 255, 255),
+    );
+
+    let (layout, fonts) = init_layout(
+        FONT_SIZE,
+        text,
+        (swatch.0 + SWATCH + PADDING) as f32,
+        (y + 3) as f32,
+    );
+    draw_text(canvas, width, (255, 255, 255, 255), &layout, &fonts);
+
+    for (i, &swatch_color) in palette.iter().enumerate() {
+        let left = x + text_end + i * (SWATCH + PALETTE_GAP);
+        draw_rect_filled(
+            canvas,
+            left,
+            swatch.1,
+            left + SWATCH,
+            swatch.1 + SWATCH,
+            width,
+            swatch_color,
+        );
+        let (outline, border) = if active == Some(i) {
+            (2, (255, 255, 255, 255))
+        } else {
+            (0, (128, 128, 128, 255))
+        };
+        draw_rect_bordered(
+            canvas,
+            left - outline,
+            swatch.1 - outline,
+            left + SWATCH + outline,
+            swatch.1 + SWATCH + outline,
+            width,
+            1 + outline / 2,
+            false,
+            border,
+        );
+    }
+}
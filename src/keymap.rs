@@ -0,0 +1,716 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use serde::{ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer};
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+use crate::DrawMode;
+
+/// Everything a hotkey can do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Save,
+    SaveFocused,
+    Tool(DrawMode),
+    Delete,
+    Weaker,
+    Stronger,
+    NextKernel,
+    ToggleFill,
+    NextColor,
+    /// Color of the palette at the index.
+    PaletteColor(usize),
+    ToggleHud,
+    ToggleToolbar,
+    ToggleHelp,
+    Thinner,
+    Thicker,
+    MoreTransparent,
+    MoreOpaque,
+    Undo,
+    Redo,
+    Exit,
+}
+
+impl Action {
+    /// Actions which also work while a text is typed.
+    pub fn is_global(self) -> bool {
+        matches!(self, Action::Undo | Action::Redo | Action::Exit)
+    }
+}
+
+/// A key pressed while exactly the given modifiers are held.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chord {
+    pub modifiers: ModifiersState,
+    pub key: VirtualKeyCode,
+}
+
+/// Names of the keys which aren't letters, digits or function keys, as shown in the help and
+/// accepted in the config file.
+const KEY_NAMES: &[(VirtualKeyCode, &str)] = &[
+    (VirtualKeyCode::Return, "Enter"),
+    (VirtualKeyCode::Back, "Backspace"),
+    (VirtualKeyCode::Escape, "Esc"),
+    (VirtualKeyCode::Tab, "Tab"),
+    (VirtualKeyCode::Space, "Space"),
+    (VirtualKeyCode::Delete, "Delete"),
+    (VirtualKeyCode::Insert, "Insert"),
+    (VirtualKeyCode::Home, "Home"),
+    (VirtualKeyCode::End, "End"),
+    (VirtualKeyCode::PageUp, "PageUp"),
+    (VirtualKeyCode::PageDown, "PageDown"),
+    (VirtualKeyCode::Up, "Up"),
+    (VirtualKeyCode::Down, "Down"),
+    (VirtualKeyCode::Left, "Left"),
+    (VirtualKeyCode::Right, "Right"),
+    (VirtualKeyCode::Comma, ","),
+    (VirtualKeyCode::Period, "."),
+    (VirtualKeyCode::Slash, "/"),
+    (VirtualKeyCode::Backslash, "\\"),
+    (VirtualKeyCode::Semicolon, ";"),
+    (VirtualKeyCode::Apostrophe, "'"),
+    (VirtualKeyCode::Grave, "`"),
+    (VirtualKeyCode::LBracket, "["),
+    (VirtualKeyCode::RBracket, "]"),
+    (VirtualKeyCode::Minus, "-"),
+    (VirtualKeyCode::Equals, "="),
+];
+
+const LETTERS: [VirtualKeyCode; 26] = {
+    use VirtualKeyCode::*;
+    [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ]
+};
+
+const DIGITS: [VirtualKeyCode; 10] = {
+    use VirtualKeyCode::*;
+    [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9]
+};
+
+const FUNCTION_KEYS: [VirtualKeyCode; 12] = {
+    use VirtualKeyCode::*;
+    [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12]
+};
+
+const MODIFIER_NAMES: [(ModifiersState, &str); 4] = [
+    (ModifiersState::CTRL, "Ctrl"),
+    (ModifiersState::ALT, "Alt"),
+    (ModifiersState::LOGO, "Super"),
+    (ModifiersState::SHIFT, "Shift"),
+];
+
+impl Chord {
+    const fn key(key: VirtualKeyCode) -> Self {
+        Self {
+            modifiers: ModifiersState::empty(),
+            key,
+        }
+    }
+
+    const fn with(modifiers: ModifiersState, key: VirtualKeyCode) -> Self {
+        Self { modifiers, key }
+    }
+
+    /// Name shown in the help, e.g. "Ctrl+Shift+Z" or "a".
+    pub fn name(&self) -> String {
+        if *self == Chord::with(ModifiersState::SHIFT, VirtualKeyCode::Slash) {
+            return "?".to_string();
+        }
+
+        let mut name = String::new();
+        for (modifier, prefix) in MODIFIER_NAMES {
+            if self.modifiers.contains(modifier) {
+                name = name + prefix + "+";
+            }
+        }
+
+        let key = if let Some(i) = LETTERS.iter().position(|key| *key == self.key) {
+            // letters are shown lowercase unless a modifier is a part of the chord
+            let letter = (b'a' + i as u8) as char;
+            if name.is_empty() {
+                letter.to_string()
+            } else {
+                letter.to_ascii_uppercase().to_string()
+            }
+        } else if let Some(i) = DIGITS.iter().position(|key| *key == self.key) {
+            i.to_string()
+        } else if let Some(i) = FUNCTION_KEYS.iter().position(|key| *key == self.key) {
+            format!("F{}", i + 1)
+        } else if let Some((_, key)) = KEY_NAMES.iter().find(|(key, _)| *key == self.key) {
+            key.to_string()
+        } else {
+            format!("{:?}", self.key)
+        };
+
+        name + &key
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    /// Parses names like "Ctrl+Shift+z", "F1" or "?". Modifiers and letters are case insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<_> = s.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap_or_default();
+
+        let mut modifiers = ModifiersState::empty();
+        for part in parts {
+            let modifier = match part.to_lowercase().as_str() {
+                "ctrl" | "control" => ModifiersState::CTRL,
+                "alt" => ModifiersState::ALT,
+                "super" | "logo" | "meta" => ModifiersState::LOGO,
+                "shift" => ModifiersState::SHIFT,
+                _ => {
+                    return Err(format!(
+                        "unknown modifier `{part}` in `{s}`, expected Ctrl, Alt, Super or Shift"
+                    ))
+                }
+            };
+            modifiers |= modifier;
+        }
+
+        let lowercase = key.to_lowercase();
+        let key = match lowercase.as_bytes() {
+            [] => return Err(format!("missing key in `{s}`")),
+            b"?" => {
+                modifiers |= ModifiersState::SHIFT;
+                VirtualKeyCode::Slash
+            }
+            [letter @ b'a'..=b'z'] => LETTERS[(letter - b'a') as usize],
+            [digit @ b'0'..=b'9'] => DIGITS[(digit - b'0') as usize],
+            _ => KEY_NAMES
+                .iter()
+                .chain(&[
+                    (VirtualKeyCode::Return, "Return"),
+                    (VirtualKeyCode::Escape, "Escape"),
+                ])
+                .find(|(_, name)| name.to_lowercase() == lowercase)
+                .map(|(key, _)| *key)
+                .or_else(|| {
+                    let number: usize = lowercase.strip_prefix('f')?.parse().ok()?;
+                    FUNCTION_KEYS.get(number.checked_sub(1)?).copied()
+                })
+                .ok_or_else(|| format!("unknown key `{key}` in `{s}`"))?,
+        };
+
+        Ok(Self { modifiers, key })
+    }
+}
+
+pub struct Hotkey {
+    pub action: Action,
+    /// Name of the action in the config file.
+    pub name: &'static str,
+    pub chords: &'static [Chord],
+    pub description: &'static str,
+}
+
+const CTRL: ModifiersState = ModifiersState::CTRL;
+const CTRL_SHIFT: ModifiersState = ModifiersState::CTRL.union(ModifiersState::SHIFT);
+
+const PALETTE_DESCRIPTION: &str = "switch to a color of the palette by its number";
+
+/// Hotkeys in the order they are listed in the help. Consecutive hotkeys with the same
+/// description share a line.
+pub const HOTKEYS: &[Hotkey] = &[
+    Hotkey {
+        action: Action::Save,
+        name: "save",
+        chords: &[Chord::key(VirtualKeyCode::Return)],
+        description: "take a screenshot of selected area, save to a clipboard and exit",
+    },
+    Hotkey {
+        action: Action::SaveFocused,
+        name: "save_focused",
+        chords: &[Chord::key(VirtualKeyCode::F)],
+        description:
+            "take a screenshot where selected area is focused, save to a clipboard and exit",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Arrow),
+        name: "arrow",
+        chords: &[Chord::key(VirtualKeyCode::A)],
+        description: "draw an arrow",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::ArrowFilled),
+        name: "filled_arrow",
+        chords: &[Chord::key(VirtualKeyCode::Z)],
+        description: "draw a filled arrow",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Line),
+        name: "line",
+        chords: &[Chord::key(VirtualKeyCode::L)],
+        description: "draw a line",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::RectBorder),
+        name: "rectangle",
+        chords: &[Chord::key(VirtualKeyCode::R)],
+        description: "draw a rectangular border",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::RectFilled),
+        name: "filled_rectangle",
+        chords: &[Chord::key(VirtualKeyCode::P)],
+        description: "draw a filled rectangle",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Ellipse),
+        name: "ellipse",
+        chords: &[Chord::key(VirtualKeyCode::E)],
+        description: "draw an ellipse (hold Shift to draw a circle)",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Path),
+        name: "freehand",
+        chords: &[Chord::key(VirtualKeyCode::D)],
+        description: "draw freehand",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Highlight),
+        name: "highlight",
+        chords: &[Chord::key(VirtualKeyCode::H)],
+        description:
+            "highlight with a translucent marker (Tab turns it into a highlighted rectangle)",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Step),
+        name: "step",
+        chords: &[Chord::key(VirtualKeyCode::N)],
+        description: "place numbered step markers, one per click (press again to stop)",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::RectBlurred),
+        name: "blur",
+        chords: &[Chord::key(VirtualKeyCode::B)],
+        description: "draw a blurred rectangle",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::RectPixelated),
+        name: "pixelate",
+        chords: &[Chord::key(VirtualKeyCode::M)],
+        description: "draw a pixelated rectangle",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::RectSecure),
+        name: "secure_redaction",
+        chords: &[Chord::key(VirtualKeyCode::X)],
+        description: "draw a securely redacted rectangle, the image isn't saved if any original pixel survives in it",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Text),
+        name: "text",
+        chords: &[Chord::key(VirtualKeyCode::T)],
+        description: "draw a text",
+    },
+    Hotkey {
+        action: Action::Tool(DrawMode::Picker),
+        name: "picker",
+        chords: &[Chord::key(VirtualKeyCode::G)],
+        description: "pick a color: click copies it to a clipboard, Ctrl+click uses it for the next drawn shapes, hold Shift to average 3x3 pixels, Tab switches between #rrggbb/rgb()/r,g,b,a formats",
+    },
+    Hotkey {
+        action: Action::Delete,
+        name: "delete",
+        chords: &[
+            Chord::key(VirtualKeyCode::Delete),
+            Chord::key(VirtualKeyCode::Back),
+        ],
+        description: "delete the selected shape",
+    },
+    Hotkey {
+        action: Action::Weaker,
+        name: "weaker",
+        chords: &[Chord::key(VirtualKeyCode::Comma)],
+        description: "decrease blur radius or pixelation block size of the selected redaction",
+    },
+    Hotkey {
+        action: Action::Stronger,
+        name: "stronger",
+        chords: &[Chord::key(VirtualKeyCode::Period)],
+        description: "increase blur radius or pixelation block size of the selected redaction",
+    },
+    Hotkey {
+        action: Action::NextKernel,
+        name: "next_kernel",
+        chords: &[Chord::key(VirtualKeyCode::K)],
+        description: "switch blur of the selected redaction between gaussian/box/motion kernels",
+    },
+    Hotkey {
+        action: Action::ToggleFill,
+        name: "toggle_fill",
+        chords: &[Chord::key(VirtualKeyCode::Tab)],
+        description: "toggle selected (or latest) drawn shape between filled/not filled states, or cycle a redaction between blurred/pixelated/solid/secure",
+    },
+    Hotkey {
+        action: Action::NextColor,
+        name: "next_color",
+        chords: &[Chord::key(VirtualKeyCode::C)],
+        description: "switch color of the next drawn shapes",
+    },
+    Hotkey {
+        action: Action::PaletteColor(0),
+        name: "color_1",
+        chords: &[Chord::key(VirtualKeyCode::Key1)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(1),
+        name: "color_2",
+        chords: &[Chord::key(VirtualKeyCode::Key2)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(2),
+        name: "color_3",
+        chords: &[Chord::key(VirtualKeyCode::Key3)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(3),
+        name: "color_4",
+        chords: &[Chord::key(VirtualKeyCode::Key4)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(4),
+        name: "color_5",
+        chords: &[Chord::key(VirtualKeyCode::Key5)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(5),
+        name: "color_6",
+        chords: &[Chord::key(VirtualKeyCode::Key6)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(6),
+        name: "color_7",
+        chords: &[Chord::key(VirtualKeyCode::Key7)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(7),
+        name: "color_8",
+        chords: &[Chord::key(VirtualKeyCode::Key8)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::PaletteColor(8),
+        name: "color_9",
+        chords: &[Chord::key(VirtualKeyCode::Key9)],
+        description: PALETTE_DESCRIPTION,
+    },
+    Hotkey {
+        action: Action::ToggleHud,
+        name: "toggle_hud",
+        chords: &[Chord::key(VirtualKeyCode::I)],
+        description: "show/hide the size and position of the selected area, the cursor position and the active tool",
+    },
+    Hotkey {
+        action: Action::ToggleToolbar,
+        name: "toggle_toolbar",
+        chords: &[Chord::key(VirtualKeyCode::O)],
+        description: "show/hide the toolbar next to the selected area",
+    },
+    Hotkey {
+        action: Action::ToggleHelp,
+        name: "toggle_help",
+        chords: &[
+            Chord::with(ModifiersState::SHIFT, VirtualKeyCode::Slash),
+            Chord::key(VirtualKeyCode::F1),
+        ],
+        description: "show/hide this help",
+    },
+    Hotkey {
+        action: Action::Thinner,
+        name: "thinner",
+        chords: &[Chord::key(VirtualKeyCode::LBracket)],
+        description: "decrease stroke width of the next drawn shapes",
+    },
+    Hotkey {
+        action: Action::Thicker,
+        name: "thicker",
+        chords: &[Chord::key(VirtualKeyCode::RBracket)],
+        description: "increase stroke width of the next drawn shapes",
+    },
+    Hotkey {
+        action: Action::MoreTransparent,
+        name: "more_transparent",
+        chords: &[Chord::key(VirtualKeyCode::Minus)],
+        description: "decrease opacity of the next drawn shapes",
+    },
+    Hotkey {
+        action: Action::MoreOpaque,
+        name: "more_opaque",
+        chords: &[Chord::key(VirtualKeyCode::Equals)],
+        description: "increase opacity of the next drawn shapes",
+    },
+    Hotkey {
+        action: Action::Undo,
+        name: "undo",
+        chords: &[Chord::with(CTRL, VirtualKeyCode::Z)],
+        description: "undo latest change",
+    },
+    Hotkey {
+        action: Action::Redo,
+        name: "redo",
+        chords: &[Chord::with(CTRL_SHIFT, VirtualKeyCode::Z)],
+        description: "redo latest undone change",
+    },
+    Hotkey {
+        action: Action::Exit,
+        name: "exit",
+        chords: &[Chord::key(VirtualKeyCode::Escape)],
+        description: "exit",
+    },
+];
+
+/// Mouse gestures, listed in the help after the hotkeys.
+pub const MOUSE_GESTURES: &[(&str, &str)] = &[
+    (
+        "Drag on empty space without an active tool",
+        "select a new area",
+    ),
+    (
+        "Drag inside the selected area without an active tool (or with Ctrl held)",
+        "move the selected area",
+    ),
+    (
+        "Click on a drawn shape without an active tool",
+        "select it, drag it or its handles to move and resize",
+    ),
+    (
+        "Mouse wheel",
+        "increase/decrease blur radius or pixelation block size of the selected redaction",
+    ),
+];
+
+/// Chords bound to every action of `HOTKEYS`, in the same order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<Vec<Chord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: HOTKEYS
+                .iter()
+                .map(|hotkey| hotkey.chords.to_vec())
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Rebinds the named actions to the given chords, an empty list unbinds an action. Fails on
+    /// unknown actions or keys and on chords bound to more than one action.
+    pub fn remap<'a>(
+        mut self,
+        overrides: impl IntoIterator<Item = (&'a str, Vec<&'a str>)>,
+    ) -> Result<Self, String> {
+        for (name, chords) in overrides {
+            let index = HOTKEYS
+                .iter()
+                .position(|hotkey| hotkey.name == name)
+                .ok_or_else(|| {
+                    let names: Vec<_> = HOTKEYS.iter().map(|hotkey| hotkey.name).collect();
+                    format!(
+                        "unknown action `{name}`, expected one of {}",
+                        names.join(", ")
+                    )
+                })?;
+            self.bindings[index] = chords
+                .into_iter()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?;
+        }
+
+        for (i, chords) in self.bindings.iter().enumerate() {
+            for chord in chords {
+                if let Some(j) =
+                    (i + 1..self.bindings.len()).find(|j| self.bindings[*j].contains(chord))
+                {
+                    return Err(format!(
+                        "`{}` is bound to both {} and {}",
+                        chord.name(),
+                        HOTKEYS[i].name,
+                        HOTKEYS[j].name
+                    ));
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// The action bound to `key` pressed with exactly `modifiers` held.
+    pub fn action_for(&self, key: VirtualKeyCode, modifiers: ModifiersState) -> Option<Action> {
+        let chord = Chord { modifiers, key };
+
+        self.bindings
+            .iter()
+            .position(|chords| chords.contains(&chord))
+            .map(|i| HOTKEYS[i].action)
+    }
+
+    /// Keys and descriptions of every bound hotkey and of the mouse gestures.
+    pub fn help_lines(&self) -> Vec<(String, &'static str)> {
+        let mut lines: Vec<(Vec<String>, &'static str)> = vec![];
+        for (hotkey, chords) in HOTKEYS.iter().zip(&self.bindings) {
+            let keys = chords.iter().map(Chord::name);
+            match lines.last_mut() {
+                Some((shared, description)) if *description == hotkey.description => {
+                    shared.extend(keys)
+                }
+                _ if chords.is_empty() => {}
+                _ => lines.push((keys.collect(), hotkey.description)),
+            }
+        }
+
+        let gestures = MOUSE_GESTURES
+            .iter()
+            .map(|(gesture, description)| (gesture.to_string(), *description));
+        lines
+            .into_iter()
+            .map(|(keys, description)| (keys.join(" / "), description))
+            .chain(gestures)
+            .collect()
+    }
+}
+
+/// Written as a table of action names and their chords, a single chord as a plain string.
+impl Serialize for Keymap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(HOTKEYS.len()))?;
+        for (hotkey, chords) in HOTKEYS.iter().zip(&self.bindings) {
+            let names: Vec<_> = chords.iter().map(Chord::name).collect();
+            match names.as_slice() {
+                [name] => map.serialize_entry(hotkey.name, name)?,
+                _ => map.serialize_entry(hotkey.name, &names)?,
+            }
+        }
+
+        map.end()
+    }
+}
+
+/// Read as changes of the default keymap.
+impl<'de> Deserialize<'de> for Keymap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Chords {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        let overrides = BTreeMap::<String, Chords>::deserialize(deserializer)?;
+        let overrides = overrides.iter().map(|(name, chords)| {
+            let chords = match chords {
+                Chords::One(chord) => vec![chord.as_str()],
+                Chords::Many(chords) => chords.iter().map(String::as_str).collect(),
+            };
+            (name.as_str(), chords)
+        });
+
+        Keymap::default()
+            .remap(overrides)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Help of the command line, listing what can be done while running with the default keys.
+pub fn hotkeys_help() -> String {
+    let lines: Vec<_> = Keymap::default()
+        .help_lines()
+        .into_iter()
+        .map(|(keys, description)| format!("{keys} - {description}"))
+        .collect();
+
+    format!(
+        "Hotkeys while running (see lower for cli args):\n\n{}",
+        lines.join("\n\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn every_chord_maps_to_one_listed_action() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.clone().remap([]), Ok(keymap.clone()));
+
+        let help = hotkeys_help();
+        for hotkey in HOTKEYS {
+            for chord in hotkey.chords {
+                assert_eq!(
+                    keymap.action_for(chord.key, chord.modifiers),
+                    Some(hotkey.action)
+                );
+                assert_eq!(chord.name().parse(), Ok(*chord));
+                assert!(help.contains(&chord.name()));
+            }
+            assert!(help.contains(hotkey.description));
+        }
+        assert_eq!(
+            keymap.action_for(
+                VirtualKeyCode::Z,
+                ModifiersState::CTRL | ModifiersState::SHIFT
+            ),
+            Some(Action::Redo)
+        );
+        assert_eq!(
+            keymap.action_for(VirtualKeyCode::Z, ModifiersState::ALT),
+            None
+        );
+    }
+
+    #[test]
+    fn keys_are_remapped_from_config() {
+        let config = Config::parse(
+            "[keys]\nrectangle = \"f\"\nsave_focused = [\"Ctrl+Shift+f\", \"F5\"]\nblur = []\n",
+        )
+        .unwrap();
+        let keys = &config.keys;
+        let none = ModifiersState::empty();
+        assert_eq!(
+            keys.action_for(VirtualKeyCode::F, none),
+            Some(Action::Tool(DrawMode::RectBorder))
+        );
+        assert_eq!(keys.action_for(VirtualKeyCode::R, none), None);
+        assert_eq!(keys.action_for(VirtualKeyCode::B, none), None);
+        assert_eq!(
+            keys.action_for(VirtualKeyCode::F5, none),
+            Some(Action::SaveFocused)
+        );
+        assert_eq!(
+            keys.action_for(
+                VirtualKeyCode::F,
+                ModifiersState::CTRL | ModifiersState::SHIFT
+            ),
+            Some(Action::SaveFocused)
+        );
+        assert!(!keys
+            .help_lines()
+            .iter()
+            .any(|(_, description)| *description == "draw a blurred rectangle"));
+        assert_eq!(Config::parse(&config.to_toml()), Ok(config.clone()));
+
+        let error = |content: &str| Config::parse(content).unwrap_err();
+        assert!(error("[keys]\nrectangle = \"f\"")
+            .contains("`f` is bound to both save_focused and rectangle"));
+        assert!(error("[keys]\nrect = \"q\"").contains("unknown action `rect`"));
+        assert!(error("[keys]\nline = \"Hyper+q\"").contains("unknown modifier `Hyper`"));
+        assert!(error("[keys]\nline = \"Ctrl+\"").contains("missing key in `Ctrl+`"));
+    }
+}
@@ -1,3 +1,9 @@
+use crate::{
+    antialias::{draw_line_aa, plot},
+    blend::BlendMode,
+    stroke::{draw_polyline, Cap, Join, Stroke},
+};
+
 pub fn draw_line(
     canvas: &mut [u8],
     x0: usize,
@@ -7,11 +13,6 @@ pub fn draw_line(
     width: usize,
     color: (u8, u8, u8, u8),
 ) {
-    let red = color.0;
-    let green = color.1;
-    let blue = color.2;
-    let alpha = color.3;
-
     let mut steep = false;
 
     let mut x0 = x0;
@@ -36,17 +37,44 @@ pub fn draw_line(
         let y = (y0 as f64 * (1. - t) + y1 as f64 * t) - 1.;
 
         if steep {
-            if canvas.get(x * (width * 4) + (y as usize * 4) + 3).is_some() {
-                canvas[x * (width * 4) + (y as usize * 4)] = red;
-                canvas[x * (width * 4) + (y as usize * 4) + 1] = green;
-                canvas[x * (width * 4) + (y as usize * 4) + 2] = blue;
-                canvas[x * (width * 4) + (y as usize * 4) + 3] = alpha;
-            }
-        } else if canvas.get(y as usize * (width * 4) + (x * 4) + 3).is_some() {
-            canvas[y as usize * (width * 4) + (x * 4)] = red;
-            canvas[y as usize * (width * 4) + (x * 4) + 1] = green;
-            canvas[y as usize * (width * 4) + (x * 4) + 2] = blue;
-            canvas[y as usize * (width * 4) + (x * 4) + 3] = alpha;
+            plot(canvas, width, y as isize, x as isize, color, 1.);
+        } else {
+            plot(canvas, width, x as isize, y as isize, color, 1.);
         }
     }
 }
+
+/// Draws a line `thickness` pixels wide with round caps.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_line_thick(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    thickness: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    let (p0, p1) = ((x0 as f64, y0 as f64), (x1 as f64, y1 as f64));
+
+    match (thickness, antialiasing) {
+        (0 | 1, true) => draw_line_aa(canvas, p0, p1, width, color),
+        (0 | 1, false) => draw_line(canvas, x0, y0, x1, y1, width, color),
+        _ => draw_polyline(
+            canvas,
+            width,
+            &[p0, p1],
+            false,
+            Stroke {
+                width: thickness as f64,
+                cap: Cap::Round,
+                join: Join::Round,
+            },
+            antialiasing,
+            color,
+            BlendMode::SourceOver,
+        ),
+    }
+}
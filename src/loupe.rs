@@ -0,0 +1,125 @@
+use crate::{
+    rectangle::{draw_rect_bordered, draw_rect_filled},
+    text::{draw_text, init_layout, text_width},
+    Pos2,
+};
+
+/// How many times the pixels around the cursor are enlarged.
+pub const ZOOM: usize = 8;
+/// Side of the magnified area in screen pixels, odd so the cursor's pixel is in the middle.
+pub const REGION: usize = 15;
+/// Distance between the cursor and the loupe.
+const OFFSET: usize = 24;
+const LABEL_HEIGHT: usize = 20;
+const FONT_SIZE: f32 = 13.;
+const GRID_COLOR: (u8, u8, u8, u8) = (128, 128, 128, 90);
+
+/// Top left corner of the loupe for the cursor at `pos`, placed below and right of the cursor
+/// unless that would leave the canvas.
+pub fn loupe_origin((x, y): Pos2, width: usize, height: usize) -> Pos2 {
+    let size = REGION * ZOOM;
+    let place = |at: usize, extent: usize, max: usize| {
+        if at + OFFSET + extent <= max {
+            at + OFFSET
+        } else {
+            at.saturating_sub(OFFSET + extent)
+        }
+    };
+
+    (place(x, size, width), place(y, size + LABEL_HEIGHT, height))
+}
+
+/// Draws a magnified view of `source` around `pos` with a pixel grid, a crosshair through the
+/// cursor's row and column, the cursor's pixel outlined and `label` written below.
+pub fn draw_loupe(
+    canvas: &mut [u8],
+    width: usize,
+    source: &[u8],
+    pos: Pos2,
+    label: &str,
+    color: (u8, u8, u8, u8),
+) {
+    let height = canvas.len() / (width * 4).max(1);
+    let (left, top) = loupe_origin(pos, width, height);
+    let size = REGION * ZOOM;
+    let half = REGION / 2;
+
+    for row in 0..REGION {
+        for column in 0..REGION {
+            let x = (pos.0 + column).checked_sub(half).filter(|x| *x < width);
+            let y = (pos.1 + row).checked_sub(half).filter(|y| *y < height);
+            let pixel = match (x, y) {
+                (Some(x), Some(y)) => sample(source, width, (x, y)),
+                _ => (0, 0, 0, 255),
+            };
+
+            let (x0, y0) = (left + column * ZOOM, top + row * ZOOM);
+            draw_rect_filled(canvas, x0, y0, x0 + ZOOM, y0 + ZOOM, width, pixel);
+        }
+    }
+
+    for i in 1..REGION {
+        let (x, y) = (left + i * ZOOM, top + i * ZOOM);
+        draw_rect_filled(canvas, x, top, x + 1, top + size, width, GRID_COLOR);
+        draw_rect_filled(canvas, left, y, left + size, y + 1, width, GRID_COLOR);
+    }
+
+    let (cx, cy) = (left + half * ZOOM, top + half * ZOOM);
+    let (mx, my) = (cx + ZOOM / 2, cy + ZOOM / 2);
+    draw_rect_filled(canvas, left, my, cx, my + 1, width, color);
+    draw_rect_filled(canvas, cx + ZOOM, my, left + size, my + 1, width, color);
+    draw_rect_filled(canvas, mx, top, mx + 1, cy, width, color);
+    draw_rect_filled(canvas, mx, cy + ZOOM, mx + 1, top + size, width, color);
+    draw_rect_bordered(
+        canvas,
+        cx.saturating_sub(1),
+        cy.saturating_sub(1),
+        cx + ZOOM + 1,
+        cy + ZOOM + 1,
+        width,
+        2,
+        false,
+        color,
+    );
+
+    let (layout, _) = init_layout(FONT_SIZE, label, 0., 0.);
+    let text_width = text_width(&layout);
+    let bottom = top + size + LABEL_HEIGHT;
+    draw_rect_filled(
+        canvas,
+        left,
+        top + size,
+        left + size.max(text_width + 8),
+        bottom,
+        width,
+        (0, 0, 0, 255),
+    );
+    let (layout, fonts) = init_layout(FONT_SIZE, label, (left + 4) as f32, (top + size + 3) as f32);
+    draw_text(canvas, width, (255, 255, 255, 255), &layout, &fonts);
+
+    draw_rect_bordered(
+        canvas,
+        left.saturating_sub(2),
+        top.saturating_sub(2),
+        left + size + 2,
+        top + size + 2,
+        width,
+        2,
+        false,
+        color,
+    );
+}
+
+/// The pixel's color as decimal RGB values and as hex.
+pub fn pixel_label((r, g, b, _): (u8, u8, u8, u8)) -> String {
+    format!("{r},{g},{b} #{r:02x}{g:02x}{b:02x}")
+}
+
+fn sample(source: &[u8], width: usize, (x, y): Pos2) -> (u8, u8, u8, u8) {
+    let i = y * (width * 4) + (x * 4);
+
+    match source.get(i..i + 4) {
+        Some(&[r, g, b, _]) => (r, g, b, 255),
+        _ => (0, 0, 0, 255),
+    }
+}
@@ -1,6 +1,7 @@
 #![forbid(unsafe_code)]
 
 use std::{
+    fmt,
     path::{Path, PathBuf},
     str::FromStr,
     thread::sleep,
@@ -10,42 +11,87 @@ use std::{
 #[cfg(target_os = "linux")]
 use arboard::{Clipboard, ImageData};
 use arrow::{draw_arrow_bordered, draw_arrow_filled};
-use blur::draw_rect_blurred;
+use blur::{BlurCache, BlurKernel};
 use chrono::{DateTime, Utc};
-use clap::Parser;
+use circle::{draw_ellipse_bordered, draw_ellipse_filled};
+use clap::{Parser, Subcommand};
+use color::parse_color;
+use config::{config_path, Config};
+use desktop::{
+    compose, copy_view, display_area, layout, monitor_bounds, nearest_monitor, Area, ScreenCapture,
+};
 use error_iter::ErrorIter as _;
+use help::draw_help;
+use highlight::{draw_highlight_filled, draw_highlight_stroke};
+use history::{Edit, History};
+use hit_test::{handles, hit_handle, hit_item, move_handle, translate, Grab, HANDLE_SIZE};
+use hud::{draw_hud, hud_text};
 use image::ColorType;
 use keycode_to_text::{handle_key_press, Cursor};
-use line::draw_line;
+use keymap::{hotkeys_help, Action};
+use line::draw_line_thick;
 use log::error;
+use loupe::{draw_loupe, pixel_label};
+use path::{draw_path, simplify, SIMPLIFY_TOLERANCE};
+use picker::{pick_color, ColorFormat};
 use pixels::{Pixels, SurfaceTexture};
+use rect::Rect;
 use rectangle::{draw_rect_bordered, draw_rect_filled};
+use redact::{draw_redaction, verify_redaction, Redaction, RedactionIssue};
 use screenshots::Screen;
-use serde::{Deserialize, Serialize};
-use text::{draw_cursor, draw_text, init_layout};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use step::draw_step;
+use style::Style;
+use text::{draw_cursor, draw_text, init_layout, text_bounds};
+use toolbar::{Button, Toolbar};
 use winit::{
     dpi::PhysicalPosition,
-    event::{ElementState, Event, KeyboardInput, VirtualKeyCode, WindowEvent},
+    event::{
+        ElementState, Event, KeyboardInput, ModifiersState, MouseScrollDelta, VirtualKeyCode,
+        WindowEvent,
+    },
     event_loop::{ControlFlow, EventLoop},
     platform::run_return::EventLoopExtRunReturn,
-    window::{CursorIcon, Fullscreen, WindowBuilder},
+    window::{CursorIcon, Fullscreen, Window, WindowBuilder},
 };
 use winit_input_helper::WinitInputHelper;
 
-const BORDER_WIDTH: usize = 2;
+/// Size in pixels of the text of notices.
+const NOTICE_FONT_SIZE: f32 = 16.;
+/// Distance of notices from the corner of the selection.
+const NOTICE_MARGIN: usize = 10;
 
+mod antialias;
 mod arrow;
 mod blend;
 mod blur;
 mod circle;
+mod color;
+mod config;
+mod desktop;
+mod help;
+mod highlight;
+mod history;
+mod hit_test;
+mod hud;
 mod keycode_to_text;
+mod keymap;
 mod line;
+mod loupe;
+mod path;
+mod picker;
 mod point;
+mod rect;
 mod rectangle;
+mod redact;
+mod step;
+mod stroke;
+mod style;
 mod text;
+mod toolbar;
 mod triangle;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct BorderColor {
     r: u8,
     g: u8,
@@ -64,6 +110,32 @@ impl Default for BorderColor {
     }
 }
 
+impl BorderColor {
+    const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+}
+
+impl fmt::Display for BorderColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.r, self.g, self.b, self.a)
+    }
+}
+
+impl Serialize for BorderColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BorderColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let color = String::deserialize(deserializer)?;
+
+        parse_color(&color).map_err(|e| serde::de::Error::custom(format!("`{color}`: {e}")))
+    }
+}
+
 impl From<BorderColor> for (u8, u8, u8, u8) {
     fn from(value: BorderColor) -> Self {
         (value.r, value.g, value.b, value.a)
@@ -74,53 +146,23 @@ impl FromStr for BorderColor {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let split: Result<Vec<u8>, _> = s.split(',').map(|s| s.parse()).collect();
-
-        match split {
-            Ok(v) => {
-                if v.len() != 4 {
-                    Err("Incorrect number of u8 values.".to_string())
-                } else {
-                    Ok(Self {
-                        r: v[0],
-                        g: v[1],
-                        b: v[2],
-                        a: v[3],
-                    })
-                }
-            }
-            Err(e) => Err(format!("Bad value: {e}")),
-        }
+        parse_color(s)
     }
 }
 
-///Hotkeys while running (see lower for cli args):
-///
-///  Enter - take a screenshot of selected area, save to a clipboard and exit
-///
-///  f - take a screenshot where selected area is focused, save to a clipboard and exit
-///
-///  a - draw an arrow
-///
-///  z - draw a filled arrow
-///
-///  l - draw a line
-///
-///  r - draw a rectangular border
-///
-///  p - draw a filled rectangle
-///
-///  b - draw a blurred rectangle
-///
-///  t - draw a text
-///
-///  Tab - toggle latest drawn shape between filled/not filled states
-///
-///  Esc - exit
 #[derive(Parser)]
+#[command(
+    about = "Hotkeys while running (see lower for cli args):",
+    long_about = hotkeys_help()
+)]
 struct BirdyArgs {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// color of the selection border and of drawn shapes, e.g. "#ff00ff", "magenta",
+    /// "rgb(255 0 255 / 50%)", "hsl(300, 100%, 50%)" or "255,0,255,255"
     #[arg(short, long)]
     border_color: Option<BorderColor>,
+    /// index of the only captured screen, every screen is captured by default
     #[arg(short, long)]
     screen: Option<usize>,
     /// save directory
@@ -129,6 +171,59 @@ struct BirdyArgs {
     /// save to clipboard instead of path
     #[arg(short, long)]
     clipboard: bool,
+    /// draw shapes with crisp pixels instead of anti-aliased edges
+    #[arg(long)]
+    no_antialiasing: bool,
+    /// size in pixels of the blocks of pixelated rectangles
+    #[arg(long)]
+    pixelate_block_size: Option<usize>,
+    /// radius in pixels of blurred rectangles
+    #[arg(long)]
+    blur_radius: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the config file ($XDG_CONFIG_HOME/birdy/config.toml)
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print the effective settings: the config file overridden by the given flags
+    Print,
+}
+
+impl BirdyArgs {
+    /// Overrides settings of the config file with the given flags, only the ones they set.
+    fn apply(&self, mut config: Config) -> Config {
+        if let Some(border_color) = self.border_color {
+            config.border_color = border_color;
+        }
+        if self.screen.is_some() {
+            config.screen = self.screen;
+        }
+        if self.dir.is_some() {
+            config.dir = self.dir.clone();
+        }
+        if self.clipboard {
+            config.clipboard = true;
+        }
+        if self.no_antialiasing {
+            config.antialiasing = false;
+        }
+        if let Some(block_size) = self.pixelate_block_size {
+            config.pixelate_block_size = block_size;
+        }
+        if let Some(radius) = self.blur_radius {
+            config.blur_radius = radius;
+        }
+
+        config
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -138,15 +233,42 @@ struct Image {
     pub bytes: Vec<u8>,
 }
 
+/// Where an exported image goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Destination {
+    Clipboard,
+    Dir,
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let BirdyArgs {
-        border_color,
-        screen,
-        dir,
-        clipboard,
-    } = BirdyArgs::parse();
-
-    let clipboard = match (clipboard, &dir) {
+    let args = BirdyArgs::parse();
+    let path = config_path();
+    let config = match &path {
+        Some(path) => Config::load(path).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            std::process::exit(1);
+        }),
+        None => Config::default(),
+    };
+    let mut config = args.apply(config);
+    if let Err(err) = config.validate() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+
+    if let Some(Command::Config {
+        command: ConfigCommand::Print,
+    }) = args.command
+    {
+        match &path {
+            Some(path) => println!("# {}\n", path.display()),
+            None => println!("# no config directory, neither XDG_CONFIG_HOME nor HOME is set\n"),
+        }
+        print!("{}", config.to_toml());
+        return Ok(());
+    }
+
+    config.clipboard = match (config.clipboard, &config.dir) {
         (true, Some(_)) => {
             println!("Provided save dir not used when saving to clipboard.");
             true
@@ -158,43 +280,97 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         (c, _) => c,
     };
 
-    let screens = Screen::all()?;
-    let original_screenshot = if let Some(screen) = screens.get(screen.unwrap_or(0)) {
-        let image = screen.capture()?;
-        image.to_vec()
-    } else {
-        panic!("can't find an available screen for a screenshot");
-    };
-
     env_logger::init();
     let mut event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
-    let window = WindowBuilder::new()
-        .with_title("Hello Pixels")
-        .with_fullscreen(Some(Fullscreen::Borderless(None)))
-        .with_maximized(true)
-        .build(&event_loop)?;
-
-    let mut pixels = {
-        let window_size = window.inner_size();
-        let surface_texture = SurfaceTexture::new(window_size.width, window_size.height, &window);
-        Pixels::new(window_size.width, window_size.height, surface_texture)?
-    };
-
-    let mut screenshot = Screenshot::new(
-        original_screenshot,
-        window.inner_size().width as usize,
-        window.inner_size().height as usize,
-        border_color.unwrap_or_default(),
-        dir,
-        clipboard,
-    );
 
-    let ret_code = event_loop.run_return(move |event, _, control_flow| {
-        if let Event::RedrawRequested(_) = event {
-            screenshot.draw(pixels.frame_mut());
+    let mut screens = Screen::all()?;
+    if let Some(index) = config.screen {
+        screens = screens.get(index).into_iter().copied().collect();
+    }
+    if screens.is_empty() {
+        panic!("can't find an available screen for a screenshot");
+    }
+    let monitors: Vec<_> = event_loop.available_monitors().collect();
+    let monitor_areas: Vec<_> = monitors.iter().map(Area::from).collect();
+    let mut captures = vec![];
+    let mut targets = vec![];
+    for screen in &screens {
+        let area = display_area(&screen.display_info);
+        let monitor = nearest_monitor(area, &monitor_areas);
+        targets.push(monitor.map(|i| monitors[i].clone()));
+        captures.push(ScreenCapture {
+            area: monitor.map_or(area, |i| monitor_areas[i]),
+            image: screen.capture()?,
+        });
+    }
+    let mut areas: Vec<_> = captures.iter().map(|capture| capture.area).collect();
+    layout(&mut areas);
+    for (capture, area) in captures.iter_mut().zip(&areas) {
+        capture.area = *area;
+    }
+    let desktop = compose(&captures);
+    let bounds = monitor_bounds(areas).expect("there is at least one screen");
+
+    // Windows can't be placed on Wayland, so every monitor gets a fullscreen window showing its
+    // part of the desktop.
+    let mut views = vec![];
+    for (capture, monitor) in captures.iter().zip(targets) {
+        let window = WindowBuilder::new()
+            .with_title("Hello Pixels")
+            .with_fullscreen(Some(Fullscreen::Borderless(monitor)))
+            .with_maximized(true)
+            .build(&event_loop)?;
+        let size = window.inner_size();
+        let surface_texture = SurfaceTexture::new(size.width, size.height, &window);
+        let pixels = Pixels::new(capture.area.width, capture.area.height, surface_texture)?;
+        let offset = (
+            (capture.area.x - bounds.x) as usize,
+            (capture.area.y - bounds.y) as usize,
+        );
+        views.push(View {
+            window,
+            pixels,
+            offset,
+        });
+    }
 
-            if let Err(err) = pixels.render() {
+    let (width, height) = (desktop.width() as usize, desktop.height() as usize);
+    let mut screenshot = Screenshot::new(desktop.into_raw(), width, height, config);
+    screenshot.screens = views
+        .iter()
+        .zip(&captures)
+        .map(|(view, capture)| {
+            let (right, bottom) = (
+                view.offset.0 + capture.area.width as usize,
+                view.offset.1 + capture.area.height as usize,
+            );
+            Rect::new(view.offset, (right, bottom), (width, height))
+        })
+        .collect();
+    let mut frame = vec![0; width * height * 4];
+    let mut stale = true;
+
+    let mut copied_color = false;
+    let mut copied_image = false;
+    let ret_code = event_loop.run_return(|event, _, control_flow| {
+        if let Event::RedrawRequested(window_id) = event {
+            if std::mem::take(&mut stale) {
+                screenshot.draw(&mut frame);
+            }
+            let Some(view) = views.iter_mut().find(|view| view.window.id() == window_id) else {
+                return;
+            };
+            let view_width = view.pixels.texture().width() as usize;
+            copy_view(
+                &frame,
+                width,
+                view.offset,
+                view.pixels.frame_mut(),
+                view_width,
+            );
+
+            if let Err(err) = view.pixels.render() {
                 log_error("pixels.render", err);
                 *control_flow = ControlFlow::Exit;
                 return;
@@ -203,26 +379,55 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         match event {
             Event::WindowEvent {
+                window_id,
                 event: WindowEvent::MouseInput { state, .. },
-                ..
             } => {
                 if let ElementState::Pressed = state {
                     screenshot.on_mouse_pressed();
+
+                    if let Some(color) = screenshot.picked_color.take() {
+                        match Clipboard::new().and_then(|mut ctx| ctx.set_text(color.clone())) {
+                            Ok(()) => {
+                                println!("Copied {color} to the clipboard.");
+                                copied_color = true;
+                            }
+                            Err(err) => log_error("clipboard.set_text", err),
+                        }
+                    }
+                    if let Some(destination) = screenshot.export_requested.take() {
+                        if screenshot.export(true, destination) {
+                            copied_image = destination == Destination::Clipboard;
+                            *control_flow = ControlFlow::Exit;
+                            return;
+                        }
+                    }
                 } else {
                     screenshot.on_mouse_released();
-                    window.set_cursor_icon(CursorIcon::Default);
+                    if let Some(view) = views.iter().find(|view| view.window.id() == window_id) {
+                        view.window.set_cursor_icon(CursorIcon::Default);
+                    }
                 }
-
-                window.request_redraw();
             }
 
             Event::WindowEvent {
+                window_id,
                 event: WindowEvent::CursorMoved { position, .. },
-                ..
             } => {
-                screenshot.on_mouse_move(position);
+                let Some(view) = views.iter().find(|view| view.window.id() == window_id) else {
+                    return;
+                };
+                screenshot.on_mouse_move(view.to_desktop(position, (width, height)));
 
                 let cursor = match screenshot.what_resize_opt() {
+                    _ if screenshot.is_dragging_item() || screenshot.is_moving_selection() => {
+                        CursorIcon::Grabbing
+                    }
+                    _ if screenshot.what_toolbar_button().is_some() => CursorIcon::Hand,
+                    _ if screenshot.draw_mode.is_none()
+                        && screenshot.what_item_grab().is_some() =>
+                    {
+                        CursorIcon::Move
+                    }
                     BoundaryResize::Top => CursorIcon::NResize,
                     BoundaryResize::TopLeft => CursorIcon::NwResize,
                     BoundaryResize::TopRight => CursorIcon::NeResize,
@@ -233,7 +438,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     BoundaryResize::Left => CursorIcon::WResize,
                     _ => CursorIcon::Default,
                 };
-                window.set_cursor_icon(cursor);
+                view.window.set_cursor_icon(cursor);
+            }
+
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                let up = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y > 0.,
+                    MouseScrollDelta::PixelDelta(PhysicalPosition { y, .. }) => y > 0.,
+                };
+                if up {
+                    screenshot.edit_selected_redaction(Redaction::stronger);
+                } else {
+                    screenshot.edit_selected_redaction(Redaction::weaker);
+                }
+            }
+
+            Event::WindowEvent {
+                event: WindowEvent::ModifiersChanged(state),
+                ..
+            } => {
+                screenshot.modifiers = state;
             }
 
             Event::WindowEvent {
@@ -249,51 +476,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     },
                 ..
             } => {
-                if let Some(DrawMode::Text) = &screenshot.draw_mode {
-                    screenshot.handle_input_text_keypress(event);
-                } else {
-                    if let Some(VirtualKeyCode::Return) = virtual_keycode {
-                        screenshot.save_image(screenshot.get_cropped_image());
+                let action = virtual_keycode
+                    .and_then(|key| screenshot.config.keys.action_for(key, screenshot.modifiers));
+                if action.is_some() {
+                    screenshot.notice = None;
+                }
+
+                match action {
+                    Some(Action::Exit) => {
                         *control_flow = ControlFlow::Exit;
                         return;
                     }
-                    if let Some(VirtualKeyCode::F) = virtual_keycode {
-                        screenshot.save_image(screenshot.get_full_image());
+                    Some(action) if action.is_global() => screenshot.perform(action),
+                    _ if matches!(screenshot.draw_mode, Some(DrawMode::Text)) => {
+                        screenshot.handle_input_text_keypress(event);
+                    }
+                    Some(action @ (Action::Save | Action::SaveFocused))
+                        if screenshot.export(action == Action::Save, screenshot.destination()) =>
+                    {
+                        copied_image = screenshot.destination() == Destination::Clipboard;
                         *control_flow = ControlFlow::Exit;
                         return;
                     }
-
-                    if let Some(VirtualKeyCode::A) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::Arrow);
-                    }
-                    if let Some(VirtualKeyCode::Z) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::ArrowFilled);
-                    }
-                    if let Some(VirtualKeyCode::L) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::Line);
-                    }
-                    if let Some(VirtualKeyCode::R) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::RectBorder);
-                    }
-                    if let Some(VirtualKeyCode::P) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::RectFilled);
-                    }
-                    if let Some(VirtualKeyCode::B) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::RectBlurred);
-                    }
-                    if let Some(VirtualKeyCode::T) = virtual_keycode {
-                        screenshot.draw_mode = Some(DrawMode::Text);
-                    }
-                    if let Some(VirtualKeyCode::Tab) = virtual_keycode {
-                        screenshot.toggle_filling_latest();
-                    }
+                    Some(action) => screenshot.perform(action),
+                    None => {}
                 }
 
                 if screenshot.draw_mode.is_some() {
-                    window.set_cursor_icon(CursorIcon::Crosshair);
+                    for view in &views {
+                        view.window.set_cursor_icon(CursorIcon::Crosshair);
+                    }
                 }
+            }
 
-                window.request_redraw();
+            Event::WindowEvent {
+                window_id,
+                event: WindowEvent::Resized(size),
+            } => {
+                let Some(view) = views.iter_mut().find(|view| view.window.id() == window_id) else {
+                    return;
+                };
+                if let Err(err) = view.pixels.resize_surface(size.width, size.height) {
+                    log_error("pixels.resize_surface", err);
+                    *control_flow = ControlFlow::Exit;
+                    return;
+                }
             }
 
             _ => {}
@@ -301,27 +528,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         // Handle input events
         if input.update(&event) {
-            if input.key_pressed(VirtualKeyCode::Escape) || input.close_requested() {
+            if input.close_requested() {
                 *control_flow = ControlFlow::Exit;
                 return;
             }
 
-            // Resize the window
-            if let Some(size) = input.window_resized() {
-                if let Err(err) = pixels.resize_surface(size.width, size.height) {
-                    log_error("pixels.resize_surface", err);
-                    *control_flow = ControlFlow::Exit;
-                    return;
-                }
-                if let Err(err) = pixels.resize_buffer(size.width, size.height) {
-                    log_error("pixels.resize_buffer", err);
-                    *control_flow = ControlFlow::Exit;
-                    return;
-                };
-                screenshot.resize_viewport(size.width as usize, size.height as usize);
+            stale = true;
+            for view in &views {
+                view.window.request_redraw();
             }
-
-            window.request_redraw();
         }
     });
 
@@ -334,7 +549,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     #[cfg(target_os = "linux")]
     {
-        if clipboard {
+        if copied_image || copied_color {
             drop(event_loop); // closes overlay but generates "queue destroyed while proxies still attached",
                               // not ideal?
             println!("Hanging around for a minute so that clipboard contents persist.");
@@ -353,67 +568,114 @@ fn log_error<E: std::error::Error + 'static>(method_name: &str, err: E) {
     }
 }
 
+/// Fullscreen window on one monitor, showing the part of the desktop at `offset`.
+struct View {
+    window: Window,
+    pixels: Pixels,
+    offset: Pos2,
+}
+
+impl View {
+    /// Desktop pixel under a cursor `position` in the window. A drag which leaves the window
+    /// keeps reporting positions relative to it, those are carried over to the other monitors.
+    fn to_desktop(
+        &self,
+        position: PhysicalPosition<f64>,
+        (width, height): Pos2,
+    ) -> PhysicalPosition<f64> {
+        let (x, y) = match self
+            .pixels
+            .window_pos_to_pixel((position.x as f32, position.y as f32))
+        {
+            Ok((x, y)) => (x as isize, y as isize),
+            Err(pos) => pos,
+        };
+        let x = (self.offset.0 as isize + x).clamp(0, width as isize - 1);
+        let y = (self.offset.1 as isize + y).clamp(0, height as isize - 1);
+
+        PhysicalPosition::new(x as f64, y as f64)
+    }
+}
+
 pub type Pos2 = (usize, usize);
 
 struct Screenshot {
     original_screenshot: Vec<u8>,
     modified_screenshot: Vec<u8>,
-    p0: Pos2,
-    p1: Pos2,
+    selection: Rect,
     width: usize,
     height: usize,
-    save_dir: Option<PathBuf>,
-    use_clipboard: bool,
+    config: Config,
+    /// Shown over the selection until the next click or key press, like why an export was
+    /// refused.
+    notice: Option<String>,
+    blur_cache: BlurCache,
+    show_hud: bool,
+    show_toolbar: bool,
+    show_help: bool,
+    /// Set by the copy and save buttons, the event loop exports the image there and exits.
+    export_requested: Option<Destination>,
+    color_format: ColorFormat,
+    /// Set by a click of the color picker, the event loop copies it to the clipboard.
+    picked_color: Option<String>,
+    /// Parts of the canvas shown on every monitor.
+    screens: Vec<Rect>,
 
     boundary_resize_on_press: BoundaryResize,
     draw_mode: Option<DrawMode>,
     drawing_item: Option<DrawnItem>,
     drawn_items: Vec<DrawnItem>,
-    border_color: BorderColor,
+    style: Style,
+    history: History,
+    text_history: History,
+    selection_on_press: Option<Rect>,
+    selection_drag: Option<SelectionDrag>,
+    selected_item: Option<usize>,
+    item_drag: Option<ItemDrag>,
 
     mouse_coordinates: Option<PhysicalPosition<f64>>,
+    modifiers: ModifiersState,
 }
 
 impl Screenshot {
-    fn new(
-        screenshot: Vec<u8>,
-        width: usize,
-        height: usize,
-        border_color: BorderColor,
-        save_dir: Option<PathBuf>,
-        use_clipboard: bool,
-    ) -> Self {
+    fn new(screenshot: Vec<u8>, width: usize, height: usize, config: Config) -> Self {
         Self {
             original_screenshot: screenshot.clone(),
             modified_screenshot: screenshot,
-            save_dir,
-            use_clipboard,
+            style: Style {
+                stroke_width: config.stroke_width,
+                ..Style::new(config.border_color)
+            },
+            config,
+            notice: None,
+            blur_cache: BlurCache::default(),
+            show_hud: true,
+            show_toolbar: true,
+            show_help: false,
+            export_requested: None,
+            color_format: ColorFormat::default(),
+            picked_color: None,
+            screens: vec![Rect::full(width, height)],
 
             boundary_resize_on_press: BoundaryResize::None,
             draw_mode: None,
             drawing_item: None,
             drawn_items: vec![],
-            border_color,
-
-            p0: (0, 0),
-            p1: (width, height),
+            history: History::default(),
+            text_history: History::default(),
+            selection_on_press: None,
+            selection_drag: None,
+            selected_item: None,
+            item_drag: None,
+
+            selection: Rect::full(width, height),
             width,
             height,
             mouse_coordinates: None,
+            modifiers: ModifiersState::empty(),
         }
     }
 
-    pub fn resize_viewport(&mut self, width: usize, height: usize) {
-        *self = Self::new(
-            self.original_screenshot.clone(),
-            width,
-            height,
-            self.border_color,
-            self.save_dir.clone(),
-            self.use_clipboard,
-        );
-    }
-
     fn get_full_image(&self) -> Image {
         Image {
             width: self.width,
@@ -422,13 +684,15 @@ impl Screenshot {
         }
     }
 
+    /// Area of the cropped image, inside of the selection border.
+    fn cropped_area(&self) -> Rect {
+        self.selection.inset(1 + (self.config.border_width / 2))
+    }
+
     fn get_cropped_image(&self) -> Image {
-        let ymin = self.p0.1 + 1 + (BORDER_WIDTH / 2);
-        let ymax = self.p1.1 - 1 - (BORDER_WIDTH / 2);
-        let xmin = self.p0.0 + 1 + (BORDER_WIDTH / 2);
-        let xmax = self.p1.0 - 1 - (BORDER_WIDTH / 2);
-        let height = ymax - ymin;
-        let width = xmax - xmin;
+        let area = self.cropped_area();
+        let ((xmin, ymin), (xmax, ymax)) = (area.p0(), area.p1());
+        let (width, height) = (area.width(), area.height());
         let mut bytes = Vec::with_capacity(height * width * 4);
         for y in ymin..ymax {
             for x in xmin..xmax {
@@ -446,8 +710,77 @@ impl Screenshot {
         }
     }
 
-    pub fn save_image(&self, image: Image) {
-        if self.use_clipboard {
+    /// Where the save hotkeys export to, the clipboard if the config asks for it.
+    fn destination(&self) -> Destination {
+        if self.config.clipboard {
+            Destination::Clipboard
+        } else {
+            Destination::Dir
+        }
+    }
+
+    /// Saves the cropped or the full image unless a redaction in it leaks pixels of the original
+    /// screenshot. Returns whether the image was saved.
+    pub fn export(&mut self, cropped: bool, destination: Destination) -> bool {
+        let (image, origin) = if cropped {
+            (self.get_cropped_image(), self.cropped_area().p0())
+        } else {
+            (self.get_full_image(), (0, 0))
+        };
+
+        if image.width == 0 || image.height == 0 {
+            self.notify("Not saved: the selected area is empty.".to_string());
+            return false;
+        }
+
+        if !self.check_redactions(&image, origin) {
+            return false;
+        }
+
+        self.save_image(image, destination);
+        true
+    }
+
+    /// Reports redactions which may leave original content in the exported `image` taken at
+    /// `origin`. Returns `false` if some of them leaks pixels of the original screenshot.
+    fn check_redactions(&mut self, image: &Image, origin: Pos2) -> bool {
+        let mut secure = true;
+
+        for item in self.drawn_items.clone() {
+            if let DrawnItem::Redact(p0, p1, redaction) = item {
+                match verify_redaction(
+                    &self.original_screenshot,
+                    self.width,
+                    image,
+                    origin,
+                    (p0, p1),
+                    redaction,
+                ) {
+                    Ok(()) => {}
+                    Err(RedactionIssue::Recoverable) => {
+                        println!("Blurred and pixelated areas may be recoverable, use a secure redaction for sensitive data.");
+                    }
+                    Err(RedactionIssue::Leaking(pixels)) => {
+                        self.notify(format!(
+                            "Not saved: {pixels} original pixels are recoverable in a redacted area."
+                        ));
+                        secure = false;
+                    }
+                }
+            }
+        }
+
+        secure
+    }
+
+    /// Prints `message` and shows it in the overlay, which may be the only thing visible.
+    fn notify(&mut self, message: String) {
+        println!("{message}");
+        self.notice = Some(message);
+    }
+
+    pub fn save_image(&self, image: Image, destination: Destination) {
+        if destination == Destination::Clipboard {
             let mut ctx = Clipboard::new().unwrap();
 
             let img_data = ImageData {
@@ -462,7 +795,8 @@ impl Screenshot {
             let name = format!("birdy_{now_str}.png");
 
             let fpath = self
-                .save_dir
+                .config
+                .dir
                 .as_ref()
                 .unwrap()
                 .join(Path::new(name.as_str()));
@@ -483,95 +817,282 @@ impl Screenshot {
         self.draw_boundaries();
         self.darken_not_selected_area();
 
+        // step markers are numbered by their order, so deleting one renumbers the following ones
+        let mut step = 0;
         for draw_item in self.drawn_items.clone() {
-            self.draw_draw_item(&draw_item);
+            if let DrawnItem::Step(..) = draw_item {
+                step += 1;
+            }
+            self.draw_draw_item(&draw_item, step);
         }
 
         if let Some(drawing_item) = &self.drawing_item {
-            self.draw_draw_item(&drawing_item.clone());
+            self.draw_draw_item(&drawing_item.clone(), step + 1);
         }
+        self.blur_cache.sweep();
 
         if pixels.len() == self.modified_screenshot.len() {
             pixels.copy_from_slice(&self.modified_screenshot);
+            self.draw_overlay(pixels);
+        }
+    }
+
+    /// Draws editing helpers which are shown on screen but never saved.
+    fn draw_overlay(&self, pixels: &mut [u8]) {
+        let toolbar = self.toolbar();
+        if let Some(toolbar) = &toolbar {
+            toolbar.draw(
+                pixels,
+                self.draw_mode,
+                self.style.rgba(),
+                self.config.border_color.into(),
+            );
+        }
+
+        if self.show_hud {
+            let cursor = self
+                .mouse_coordinates
+                .map(|PhysicalPosition { x, y }| (x as usize, y as usize));
+            let tool = self.draw_mode.as_ref().map_or("select", DrawMode::name);
+            let palette: Vec<_> = self.config.palette.iter().map(|&c| c.into()).collect();
+            draw_hud(
+                pixels,
+                self.width,
+                toolbar.map_or(self.selection, |toolbar| {
+                    self.selection.union(&toolbar.area())
+                }),
+                &hud_text(self.cropped_area(), cursor, tool, self.style.stroke_width),
+                self.style.rgba(),
+                &palette,
+                self.config
+                    .palette
+                    .iter()
+                    .position(|color| *color == self.style.color),
+            );
+        }
+
+        if let Some(pos) = self.loupe_position() {
+            let label = if let Some(DrawMode::Picker) = self.draw_mode {
+                self.color_format.format(self.pick_color(pos))
+            } else {
+                pixel_label(pick_color(
+                    &self.original_screenshot,
+                    self.width,
+                    pos,
+                    false,
+                ))
+            };
+            draw_loupe(
+                pixels,
+                self.width,
+                &self.original_screenshot,
+                pos,
+                &label,
+                self.config.border_color.into(),
+            );
+        }
+
+        if let Some(item) = self.selected_item.and_then(|i| self.drawn_items.get(i)) {
+            for (x, y) in handles(item) {
+                let half = HANDLE_SIZE / 2;
+                draw_rect_filled(
+                    pixels,
+                    x.saturating_sub(half),
+                    y.saturating_sub(half),
+                    x + half,
+                    y + half,
+                    self.width,
+                    self.config.border_color.into(),
+                );
+                draw_rect_filled(
+                    pixels,
+                    x.saturating_sub(half - 2),
+                    y.saturating_sub(half - 2),
+                    x + half - 2,
+                    y + half - 2,
+                    self.width,
+                    (255, 255, 255, 255),
+                );
+            }
+        }
+
+        if let Some(notice) = &self.notice {
+            let (x0, y0) = self.selection.p0();
+            let (x0, y0) = (x0 + NOTICE_MARGIN, y0 + NOTICE_MARGIN);
+            let (layout, fonts) = init_layout(NOTICE_FONT_SIZE, notice, x0 as f32, y0 as f32);
+            if let Some(((x0, y0), (x1, y1))) = text_bounds(&layout) {
+                draw_rect_filled(pixels, x0, y0, x1, y1, self.width, (0, 0, 0, 200));
+            }
+            draw_text(pixels, self.width, (255, 255, 255, 255), &layout, &fonts);
+        }
+
+        if self.show_help {
+            let screen = self.active_screen();
+            draw_help(pixels, self.width, screen, &self.config.keys.help_lines());
         }
     }
 
-    fn draw_draw_item(&mut self, draw_item: &DrawnItem) {
+    /// Draws a single item. `step` is the number shown by a step marker.
+    fn draw_draw_item(&mut self, draw_item: &DrawnItem, step: usize) {
         match &draw_item {
-            DrawnItem::Arrow((x0, y0), (x1, y1)) => {
-                draw_arrow_bordered(
+            DrawnItem::Arrow((x0, y0), (x1, y1), style) => {
+                let draw_arrow = if style.fill {
+                    draw_arrow_filled
+                } else {
+                    draw_arrow_bordered
+                };
+                draw_arrow(
                     &mut self.modified_screenshot,
                     *x0,
                     *y0,
                     *x1,
                     *y1,
                     self.width,
-                    self.border_color.into(),
+                    style.stroke_width,
+                    self.config.antialiasing,
+                    style.rgba(),
                 );
             }
-            DrawnItem::ArrowFilled((x0, y0), (x1, y1)) => {
-                draw_arrow_filled(
+            DrawnItem::Line((x0, y0), (x1, y1), style) => {
+                draw_line_thick(
                     &mut self.modified_screenshot,
                     *x0,
                     *y0,
                     *x1,
                     *y1,
                     self.width,
-                    self.border_color.into(),
+                    style.stroke_width,
+                    self.config.antialiasing,
+                    style.rgba(),
                 );
             }
-            DrawnItem::Line((x0, y0), (x1, y1)) => {
-                draw_line(
+            DrawnItem::Rect((x0, y0), (x1, y1), style) => {
+                if style.fill {
+                    draw_rect_filled(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        style.rgba(),
+                    );
+                } else {
+                    draw_rect_bordered(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        style.stroke_width,
+                        self.config.antialiasing,
+                        style.rgba(),
+                    );
+                }
+            }
+            DrawnItem::Ellipse((x0, y0), (x1, y1), style) => {
+                if style.fill {
+                    draw_ellipse_filled(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        self.config.antialiasing,
+                        style.rgba(),
+                    );
+                } else {
+                    draw_ellipse_bordered(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        style.stroke_width,
+                        self.config.antialiasing,
+                        style.rgba(),
+                    );
+                }
+            }
+            DrawnItem::Step(center, style) => {
+                draw_step(
                     &mut self.modified_screenshot,
-                    *x0,
-                    *y0,
-                    *x1,
-                    *y1,
+                    *center,
+                    step,
                     self.width,
-                    self.border_color.into(),
+                    self.config.antialiasing,
+                    style.rgba(),
                 );
             }
-            DrawnItem::RectBorder((x0, y0), (x1, y1)) => {
-                draw_rect_bordered(
+            DrawnItem::Path(points, style) => {
+                draw_path(
                     &mut self.modified_screenshot,
-                    *x0,
-                    *y0,
-                    *x1,
-                    *y1,
+                    points,
                     self.width,
-                    self.border_color.into(),
+                    style.stroke_width,
+                    self.config.antialiasing,
+                    style.rgba(),
                 );
             }
-            DrawnItem::RectBlurred((x0, y0), (x1, y1)) => {
-                draw_rect_blurred(
+            DrawnItem::Highlight((x0, y0), (x1, y1), style) => {
+                if style.fill {
+                    draw_highlight_filled(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        style.rgba(),
+                    );
+                } else {
+                    draw_highlight_stroke(
+                        &mut self.modified_screenshot,
+                        *x0,
+                        *y0,
+                        *x1,
+                        *y1,
+                        self.width,
+                        style.stroke_width,
+                        self.config.antialiasing,
+                        style.rgba(),
+                    );
+                }
+            }
+            DrawnItem::Redact((x0, y0), (x1, y1), redaction) => {
+                draw_redaction(
                     &mut self.modified_screenshot,
+                    &self.original_screenshot,
+                    &mut self.blur_cache,
                     *x0,
                     *y0,
                     *x1,
                     *y1,
                     self.width,
+                    *redaction,
                 );
             }
-            DrawnItem::Text((mut cursor, ref content, (x0, y0))) => {
-                let (layout, fonts) = init_layout(24.0, content, *x0 as f32, *y0 as f32);
-                if let (Some(first), Some(last)) = (layout.glyphs().first(), layout.glyphs().last())
-                {
+            DrawnItem::Text((mut cursor, ref content, (x0, y0)), style) => {
+                let (layout, fonts) =
+                    init_layout(self.config.font_size, content, *x0 as f32, *y0 as f32);
+                if let Some(((x0, y0), (x1, y1))) = text_bounds(&layout) {
                     draw_rect_filled(
                         &mut self.modified_screenshot,
-                        (first.x as usize).saturating_sub(5),
-                        (first.y as usize).saturating_sub(5),
-                        (last.x as usize + last.width) + 5,
-                        (last.y as usize + last.height) + 5,
+                        x0,
+                        y0,
+                        x1,
+                        y1,
                         self.width,
                         (0, 0, 0, 255),
                     );
                 }
                 draw_text(
                     &mut self.modified_screenshot,
-                    *x0,
-                    *y0,
                     self.width,
-                    self.border_color.into(),
+                    style.rgba(),
                     &layout,
                     &fonts,
                 );
@@ -583,109 +1104,385 @@ impl Screenshot {
                         &layout,
                         content,
                         (*x0, *y0),
-                        self.border_color.into(),
+                        self.config.font_size,
+                        style.rgba(),
                     );
                 }
             }
-            DrawnItem::RectFilled((x0, y0), (x1, y1)) => {
-                draw_rect_filled(
-                    &mut self.modified_screenshot,
-                    *x0,
-                    *y0,
-                    *x1,
-                    *y1,
-                    self.width,
-                    self.border_color.into(),
-                );
-            }
         }
     }
 
     fn draw_boundaries(&mut self) {
-        draw_rect_filled(
-            &mut self.modified_screenshot,
-            self.p0.0,
-            self.p0.1,
-            self.p1.0,
-            self.p0.1 + BORDER_WIDTH,
-            self.width,
-            self.border_color.into(),
-        );
-        draw_rect_filled(
-            &mut self.modified_screenshot,
-            self.p1.0 - BORDER_WIDTH,
-            self.p0.1,
-            self.p1.0,
-            self.p1.1,
-            self.width,
-            self.border_color.into(),
-        );
-        draw_rect_filled(
-            &mut self.modified_screenshot,
-            self.p0.0,
-            self.p1.1 - BORDER_WIDTH,
-            self.p1.0,
-            self.p1.1,
-            self.width,
-            self.border_color.into(),
-        );
-        draw_rect_filled(
+        draw_rect_bordered(
             &mut self.modified_screenshot,
-            self.p0.0,
-            self.p0.1,
-            self.p0.0 + BORDER_WIDTH,
-            self.p1.1,
+            self.selection.p0().0,
+            self.selection.p0().1,
+            self.selection.p1().0,
+            self.selection.p1().1,
             self.width,
-            self.border_color.into(),
+            self.config.border_width,
+            false,
+            self.config.border_color.into(),
         );
     }
 
     fn darken_not_selected_area(&mut self) {
+        let (p0, p1) = (self.selection.p0(), self.selection.p1());
         for y in 0..self.height {
             for x in 0..self.width {
-                if x < self.p0.0 || x > self.p1.0 || y < self.p0.1 || y > self.p1.1 {
-                    self.modified_screenshot[y * (self.width * 4) + (x * 4) + 3] = 100;
+                if x < p0.0 || x > p1.0 || y < p0.1 || y > p1.1 {
+                    self.modified_screenshot[y * (self.width * 4) + (x * 4) + 3] =
+                        self.config.dim_level;
                 }
             }
         }
     }
 
-    pub fn toggle_filling_latest(&mut self) {
-        if let Some(item) = self.drawn_items.pop() {
+    /// Toggles filling of the selected item, or of the latest drawn one if nothing is selected.
+    pub fn toggle_filling(&mut self) {
+        let index = self.selected_item.or(self.drawn_items.len().checked_sub(1));
+
+        if let Some(index) = index {
+            let item = self.drawn_items[index].clone();
             let filled_item = self.toggle_item_filling(&item);
-            self.drawn_items.push(filled_item);
+            if filled_item != item {
+                self.commit(Edit::Replace {
+                    index,
+                    before: item,
+                    after: filled_item,
+                });
+            }
         }
     }
 
-    pub fn toggle_item_filling(&mut self, draw_item: &DrawnItem) -> DrawnItem {
-        match draw_item {
-            DrawnItem::Arrow(p0, p1) => DrawnItem::ArrowFilled(*p0, *p1),
-            DrawnItem::ArrowFilled(p0, p1) => DrawnItem::Arrow(*p0, *p1),
-            DrawnItem::RectBorder(p0, p1) => DrawnItem::RectFilled(*p0, *p1),
-            DrawnItem::RectFilled(p0, p1) | DrawnItem::RectBlurred(p0, p1) => {
-                DrawnItem::RectBorder(*p0, *p1)
+    /// Changes the selected redaction, e.g. its blur radius.
+    pub fn edit_selected_redaction(&mut self, change: impl Fn(Redaction) -> Redaction) {
+        let Some(index) = self.selected_item else {
+            return;
+        };
+
+        if let DrawnItem::Redact(p0, p1, redaction) = self.drawn_items[index] {
+            let after = DrawnItem::Redact(p0, p1, change(redaction));
+            if after != self.drawn_items[index] {
+                self.commit(Edit::Replace {
+                    index,
+                    before: self.drawn_items[index].clone(),
+                    after,
+                });
             }
-            DrawnItem::Line(..) | DrawnItem::Text(..) => draw_item.clone(),
         }
     }
 
-    pub fn handle_input_text_keypress(&mut self, event: KeyboardInput) {
-        match event.virtual_keycode {
-            Some(VirtualKeyCode::Escape | VirtualKeyCode::Return) => {
-                if let Some(DrawnItem::Text((cursor, ref mut content, p0))) = &mut self.drawing_item
-                {
-                    self.drawn_items
-                        .push(DrawnItem::Text((*cursor, content.clone(), *p0)));
-                    self.drawing_item = None;
-                }
+    pub fn delete_selected(&mut self) {
+        if let Some(index) = self.selected_item.take() {
+            let item = self.drawn_items[index].clone();
+            self.commit(Edit::Remove { index, item });
+        }
+    }
 
-                self.draw_mode = None;
+    fn commit(&mut self, edit: Edit) {
+        self.apply(&edit);
+        self.history.record(edit);
+    }
+
+    fn apply(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Push(item) => self.drawn_items.push(item.clone()),
+            Edit::Remove { index, .. } => {
+                self.drawn_items.remove(*index);
+            }
+            Edit::Replace { index, after, .. } => self.drawn_items[*index] = after.clone(),
+            Edit::Selection { after, .. } => self.selection = *after,
+            Edit::Text { after, .. } => self.set_drawing_text(after.clone()),
+        }
+    }
+
+    fn revert(&mut self, edit: &Edit) {
+        match edit {
+            Edit::Push(_) => {
+                self.drawn_items.pop();
+            }
+            Edit::Remove { index, item } => self.drawn_items.insert(*index, item.clone()),
+            Edit::Replace { index, before, .. } => self.drawn_items[*index] = before.clone(),
+            Edit::Selection { before, .. } => self.selection = *before,
+            Edit::Text { before, .. } => self.set_drawing_text(before.clone()),
+        }
+    }
+
+    fn set_drawing_text(&mut self, text: (Cursor, String)) {
+        if let Some(DrawnItem::Text((cursor, content, _), _)) = &mut self.drawing_item {
+            (*cursor, *content) = text;
+        }
+    }
+
+    /// Reverts the latest change. While a text is being typed only its edits are reverted.
+    pub fn undo(&mut self) {
+        self.finish_item_drag();
+        if self.drawing_item.is_some() {
+            if let Some(edit) = self.text_history.undo() {
+                self.revert(&edit);
+            }
+        } else if let Some(edit) = self.history.undo() {
+            self.revert(&edit);
+            self.drop_stale_selection();
+        }
+    }
+
+    /// Reapplies the latest reverted change.
+    pub fn redo(&mut self) {
+        self.finish_item_drag();
+        if self.drawing_item.is_some() {
+            if let Some(edit) = self.text_history.redo() {
+                self.apply(&edit);
+            }
+        } else if let Some(edit) = self.history.redo() {
+            self.apply(&edit);
+            self.drop_stale_selection();
+        }
+    }
+
+    fn drop_stale_selection(&mut self) {
+        self.selected_item = self
+            .selected_item
+            .filter(|index| *index < self.drawn_items.len());
+    }
+
+    pub fn is_dragging_item(&self) -> bool {
+        self.item_drag.is_some()
+    }
+
+    /// The pixel to magnify, shown while the selection is resized or a tool places points.
+    fn loupe_position(&self) -> Option<Pos2> {
+        let PhysicalPosition { x, y } = self.mouse_coordinates?;
+        let resizing = self.boundary_resize_on_press != BoundaryResize::None
+            || matches!(self.selection_drag, Some(SelectionDrag::Create { .. }));
+        let placing =
+            self.drawing_item.is_some() && !matches!(self.draw_mode, Some(DrawMode::Text));
+        let picking = matches!(self.draw_mode, Some(DrawMode::Picker));
+
+        let on_canvas =
+            x >= 0. && y >= 0. && (x as usize) < self.width && (y as usize) < self.height;
+        (on_canvas && (resizing || placing || picking)).then_some((x as usize, y as usize))
+    }
+
+    /// Color of the original screenshot at `pos`, averaged over 3×3 pixels while Shift is held.
+    fn pick_color(&self, pos: Pos2) -> (u8, u8, u8, u8) {
+        pick_color(
+            &self.original_screenshot,
+            self.width,
+            pos,
+            self.modifiers.shift(),
+        )
+    }
+
+    /// The screen under the cursor, or the one holding the middle of the selection.
+    fn active_screen(&self) -> Rect {
+        let cursor = self
+            .mouse_coordinates
+            .map(|PhysicalPosition { x, y }| (x as usize, y as usize));
+        let (p0, p1) = (self.selection.p0(), self.selection.p1());
+        let middle = ((p0.0 + p1.0) / 2, (p0.1 + p1.1) / 2);
+
+        let under = |(x, y): Pos2| {
+            self.screens.iter().find(|screen| {
+                (screen.p0().0..screen.p1().0).contains(&x)
+                    && (screen.p0().1..screen.p1().1).contains(&y)
+            })
+        };
+        cursor
+            .and_then(under)
+            .or_else(|| under(middle))
+            .copied()
+            .unwrap_or(Rect::full(self.width, self.height))
+    }
+
+    /// The toolbar is shown once an area is selected.
+    fn toolbar(&self) -> Option<Toolbar> {
+        (self.show_toolbar && self.selection != Rect::full(self.width, self.height))
+            .then(|| Toolbar::new(self.selection, (self.width, self.height)))
+    }
+
+    pub fn what_toolbar_button(&self) -> Option<Button> {
+        let PhysicalPosition { x, y } = self.mouse_coordinates?;
+
+        self.toolbar()?.hit((x as usize, y as usize))
+    }
+
+    fn press_button(&mut self, button: Button) {
+        match button {
+            Button::Tool(mode) => self.toggle_tool(mode),
+            Button::Color => self.perform(Action::NextColor),
+            Button::Thinner => self.perform(Action::Thinner),
+            Button::Thicker => self.perform(Action::Thicker),
+            Button::Undo => self.perform(Action::Undo),
+            Button::Copy => self.export_requested = Some(Destination::Clipboard),
+            Button::Save => {
+                if self.config.dir.is_some() {
+                    self.export_requested = Some(Destination::Dir);
+                } else {
+                    self.notify("Not saved: no save directory, pass one with --dir.".to_string());
+                }
+            }
+        }
+    }
+
+    fn toggle_tool(&mut self, mode: DrawMode) {
+        self.draw_mode = if self.draw_mode == Some(mode) {
+            None
+        } else {
+            Some(mode)
+        };
+    }
+
+    /// Performs a hotkey's action. Saving and exiting are up to the event loop.
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            // the step counter hotkey also ends numbering
+            Action::Tool(DrawMode::Step) => self.toggle_tool(DrawMode::Step),
+            Action::Tool(mode) => self.draw_mode = Some(mode),
+            Action::Delete => self.delete_selected(),
+            Action::Weaker => self.edit_selected_redaction(Redaction::weaker),
+            Action::Stronger => self.edit_selected_redaction(Redaction::stronger),
+            Action::NextKernel => self.edit_selected_redaction(Redaction::next_kernel),
+            Action::ToggleFill if self.draw_mode == Some(DrawMode::Picker) => {
+                self.color_format = self.color_format.next();
+            }
+            Action::ToggleFill => self.toggle_filling(),
+            Action::NextColor => self.style.next_color(&self.config.palette),
+            Action::PaletteColor(index) => {
+                if let Some(&color) = self.config.palette.get(index) {
+                    self.style.color = color;
+                }
+            }
+            Action::ToggleHud => self.show_hud = !self.show_hud,
+            Action::ToggleToolbar => self.show_toolbar = !self.show_toolbar,
+            Action::ToggleHelp => self.show_help = !self.show_help,
+            Action::Thinner => self.style.thinner(),
+            Action::Thicker => self.style.thicker(),
+            Action::MoreTransparent => self.style.more_transparent(),
+            Action::MoreOpaque => self.style.more_opaque(),
+            Action::Undo => self.undo(),
+            Action::Redo => self.redo(),
+            Action::Save | Action::SaveFocused | Action::Exit => {}
+        }
+    }
+
+    pub fn is_moving_selection(&self) -> bool {
+        matches!(self.selection_drag, Some(SelectionDrag::Move { .. }))
+    }
+
+    /// Finds out whether pressing at `pos` starts a new selection or moves the current one. The
+    /// initial selection covers the whole screen, so dragging inside of it selects a new area.
+    fn what_selection_drag(&self, pos: Pos2) -> Option<SelectionDrag> {
+        let whole_screen = self.selection == Rect::full(self.width, self.height);
+
+        if self.selection.contains(pos)
+            && !whole_screen
+            && (self.draw_mode.is_none() || self.modifiers.ctrl())
+        {
+            Some(SelectionDrag::Move {
+                origin: pos,
+                selection: self.selection,
+            })
+        } else if self.draw_mode.is_none() {
+            Some(SelectionDrag::Create { anchor: pos })
+        } else {
+            None
+        }
+    }
+
+    /// Builds the selection from corners which may have been dragged past each other, switching
+    /// to resizing the opposite edge when that happens.
+    fn normalize_selection(&mut self, p0: Pos2, p1: Pos2) {
+        if p0.0 > p1.0 {
+            self.boundary_resize_on_press = match self.boundary_resize_on_press {
+                BoundaryResize::Left => BoundaryResize::Right,
+                BoundaryResize::Right => BoundaryResize::Left,
+                BoundaryResize::TopLeft => BoundaryResize::TopRight,
+                BoundaryResize::TopRight => BoundaryResize::TopLeft,
+                BoundaryResize::BottomLeft => BoundaryResize::BottomRight,
+                BoundaryResize::BottomRight => BoundaryResize::BottomLeft,
+                other => other,
+            };
+        }
+        if p0.1 > p1.1 {
+            self.boundary_resize_on_press = match self.boundary_resize_on_press {
+                BoundaryResize::Top => BoundaryResize::Bottom,
+                BoundaryResize::Bottom => BoundaryResize::Top,
+                BoundaryResize::TopLeft => BoundaryResize::BottomLeft,
+                BoundaryResize::BottomLeft => BoundaryResize::TopLeft,
+                BoundaryResize::TopRight => BoundaryResize::BottomRight,
+                BoundaryResize::BottomRight => BoundaryResize::TopRight,
+                other => other,
+            };
+        }
+
+        self.selection = Rect::new(p0, p1, (self.width, self.height));
+    }
+
+    /// Finds what would be grabbed by a click without an active tool: a handle of the
+    /// selected item first, then the topmost item under the cursor.
+    pub fn what_item_grab(&self) -> Option<(usize, Grab)> {
+        let PhysicalPosition { x, y } = self.mouse_coordinates?;
+        let pos = (x as usize, y as usize);
+
+        if let Some(index) = self.selected_item {
+            if let Some(handle) = hit_handle(&self.drawn_items[index], pos) {
+                return Some((index, Grab::Handle(handle)));
+            }
+        }
+
+        self.drawn_items
+            .iter()
+            .rposition(|item| hit_item(item, pos, self.config.font_size))
+            .map(|index| (index, Grab::Move))
+    }
+
+    pub fn toggle_item_filling(&mut self, draw_item: &DrawnItem) -> DrawnItem {
+        match draw_item {
+            DrawnItem::Arrow(p0, p1, style) => {
+                DrawnItem::Arrow(*p0, *p1, style.filled(!style.fill))
+            }
+            DrawnItem::Rect(p0, p1, style) => DrawnItem::Rect(*p0, *p1, style.filled(!style.fill)),
+            DrawnItem::Ellipse(p0, p1, style) => {
+                DrawnItem::Ellipse(*p0, *p1, style.filled(!style.fill))
+            }
+            DrawnItem::Highlight(p0, p1, style) => {
+                DrawnItem::Highlight(*p0, *p1, style.filled(!style.fill))
+            }
+            DrawnItem::Redact(p0, p1, redaction) => {
+                let redaction =
+                    redaction.next(self.config.blur_radius, self.config.pixelate_block_size);
+                DrawnItem::Redact(*p0, *p1, redaction)
+            }
+            DrawnItem::Line(..)
+            | DrawnItem::Step(..)
+            | DrawnItem::Path(..)
+            | DrawnItem::Text(..) => draw_item.clone(),
+        }
+    }
+
+    pub fn handle_input_text_keypress(&mut self, event: KeyboardInput) {
+        match event.virtual_keycode {
+            Some(VirtualKeyCode::Escape | VirtualKeyCode::Return) => {
+                if let Some(item @ DrawnItem::Text(..)) = self.drawing_item.take() {
+                    self.commit(Edit::Push(item));
+                    self.text_history.clear();
+                }
+
+                self.draw_mode = None;
             }
             _ => {
-                if let Some(DrawnItem::Text((ref mut cursor, ref mut content, _))) =
+                if let Some(DrawnItem::Text((ref mut cursor, ref mut content, _), _)) =
                     &mut self.drawing_item
                 {
+                    let before = (*cursor, content.clone());
                     handle_key_press(content, event, cursor);
+                    let after = (*cursor, content.clone());
+
+                    if before != after {
+                        self.text_history.record(Edit::Text { before, after });
+                    }
                 }
             }
         };
@@ -695,56 +1492,125 @@ impl Screenshot {
         self.mouse_coordinates = Some(coordinates);
         let PhysicalPosition { x, y } = coordinates;
 
-        match self.boundary_resize_on_press {
-            BoundaryResize::None => match (&self.draw_mode, &mut self.drawing_item) {
-                (Some(DrawMode::Arrow), Some(DrawnItem::Arrow(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
-                }
-                (Some(DrawMode::ArrowFilled), Some(DrawnItem::ArrowFilled(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
-                }
-                (Some(DrawMode::Line), Some(DrawnItem::Line(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
-                }
-                (Some(DrawMode::RectBorder), Some(DrawnItem::RectBorder(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
-                }
-                (Some(DrawMode::RectFilled), Some(DrawnItem::RectFilled(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
+        if let Some(drag) = &self.item_drag {
+            let pos = (x as usize, y as usize);
+            self.drawn_items[drag.index] = match drag.grab {
+                Grab::Move => translate(
+                    &drag.before,
+                    pos.0 as isize - drag.origin.0 as isize,
+                    pos.1 as isize - drag.origin.1 as isize,
+                ),
+                Grab::Handle(handle) => move_handle(&drag.before, handle, pos),
+            };
+
+            return;
+        }
+
+        if let Some(drag) = &self.selection_drag {
+            let pos = ((x as usize).min(self.width), (y as usize).min(self.height));
+            match *drag {
+                SelectionDrag::Create { anchor } => {
+                    self.selection = Rect::new(anchor, pos, (self.width, self.height));
                 }
-                (Some(DrawMode::RectBlurred), Some(DrawnItem::RectBlurred(_, p1))) => {
-                    *p1 = (x as usize, y as usize);
+                SelectionDrag::Move { origin, selection } => {
+                    self.selection = selection.translate(
+                        pos.0 as isize - origin.0 as isize,
+                        pos.1 as isize - origin.1 as isize,
+                        (self.width, self.height),
+                    );
                 }
-                _ => {}
-            },
+            }
+
+            return;
+        }
+
+        let (mut p0, mut p1) = (self.selection.p0(), self.selection.p1());
+        match self.boundary_resize_on_press {
+            BoundaryResize::None => {
+                self.drag_drawing_item((x as usize, y as usize));
+                return;
+            }
             BoundaryResize::Top => {
-                self.p0.1 = y as usize;
+                p0.1 = y as usize;
             }
             BoundaryResize::TopLeft => {
-                self.p0.1 = y as usize;
-                self.p0.0 = x as usize;
+                p0.1 = y as usize;
+                p0.0 = x as usize;
             }
             BoundaryResize::TopRight => {
-                self.p0.1 = y as usize;
-                self.p1.0 = x as usize;
+                p0.1 = y as usize;
+                p1.0 = x as usize;
             }
             BoundaryResize::Right => {
-                self.p1.0 = x as usize;
+                p1.0 = x as usize;
             }
             BoundaryResize::Bottom => {
-                self.p1.1 = y as usize;
+                p1.1 = y as usize;
             }
             BoundaryResize::BottomLeft => {
-                self.p1.1 = y as usize;
-                self.p0.0 = x as usize;
+                p1.1 = y as usize;
+                p0.0 = x as usize;
             }
             BoundaryResize::BottomRight => {
-                self.p1.1 = y as usize;
-                self.p1.0 = x as usize;
+                p1.1 = y as usize;
+                p1.0 = x as usize;
             }
             BoundaryResize::Left => {
-                self.p0.0 = x as usize;
+                p0.0 = x as usize;
+            }
+        }
+        self.normalize_selection(p0, p1);
+    }
+
+    fn drag_drawing_item(&mut self, pos: Pos2) {
+        let shift = self.modifiers.shift();
+
+        match &mut self.drawing_item {
+            Some(DrawnItem::Ellipse(p0, p1, _)) if shift => {
+                // room towards the cursor, so a circle near an edge shrinks instead of being
+                // clipped on one axis only
+                let room = |from: usize, to: usize, size: usize| {
+                    if to < from {
+                        from
+                    } else {
+                        size.saturating_sub(from)
+                    }
+                };
+                let side = pos
+                    .0
+                    .abs_diff(p0.0)
+                    .max(pos.1.abs_diff(p0.1))
+                    .min(room(p0.0, pos.0, self.width))
+                    .min(room(p0.1, pos.1, self.height));
+                let towards = |from: usize, to: usize| {
+                    if to < from {
+                        from - side
+                    } else {
+                        from + side
+                    }
+                };
+
+                *p1 = (towards(p0.0, pos.0), towards(p0.1, pos.1));
+            }
+            Some(
+                DrawnItem::Arrow(_, p1, _)
+                | DrawnItem::Line(_, p1, _)
+                | DrawnItem::Rect(_, p1, _)
+                | DrawnItem::Ellipse(_, p1, _)
+                | DrawnItem::Highlight(_, p1, _)
+                | DrawnItem::Redact(_, p1, _),
+            ) => {
+                *p1 = pos;
+            }
+            Some(DrawnItem::Step(center, _)) => {
+                *center = pos;
             }
+            Some(DrawnItem::Path(points, _)) => {
+                if points.last() != Some(&pos) {
+                    points.push(pos);
+                }
+            }
+            Some(DrawnItem::Text(..)) | None => {}
         }
     }
 
@@ -752,58 +1618,39 @@ impl Screenshot {
         if let Some(PhysicalPosition { x, y }) = self.mouse_coordinates {
             let x = x as usize;
             let y = y as usize;
+            let (p0, p1) = (self.selection.p0(), self.selection.p1());
 
             // top left resize
-            if x > self.p0.0 && x < self.p0.0 + 20 && y > self.p0.1 && y < self.p0.1 + 20 {
+            if x > p0.0 && x < p0.0 + 20 && y > p0.1 && y < p0.1 + 20 {
                 BoundaryResize::TopLeft
             // top right resize
-            } else if x < self.p1.0 && x > self.p1.0 - 20 && y > self.p0.1 && y < self.p0.1 + 20 {
+            } else if x < p1.0 && x > p1.0.saturating_sub(20) && y > p0.1 && y < p0.1 + 20 {
                 BoundaryResize::TopRight
             }
             // top resize
-            else if x > self.p0.0
-                && x < self.p1.0
-                && y >= self.p0.1.saturating_sub(10)
-                && y <= self.p0.1 + 10
-            {
+            else if x > p0.0 && x < p1.0 && y >= p0.1.saturating_sub(10) && y <= p0.1 + 10 {
                 BoundaryResize::Top
             // right resize
-            } else if y > self.p0.1
-                && y < self.p1.1
-                && x >= self.p1.0.saturating_sub(10)
-                && x <= self.p1.0 + 10
-            {
+            } else if y > p0.1 && y < p1.1 && x >= p1.0.saturating_sub(10) && x <= p1.0 + 10 {
                 BoundaryResize::Right
             }
             // bottom left resize
-            else if x > self.p0.0
-                && x < self.p0.0 + 20
-                && y > self.p1.1.saturating_sub(20)
-                && y < self.p1.1
-            {
+            else if x > p0.0 && x < p0.0 + 20 && y > p1.1.saturating_sub(20) && y < p1.1 {
                 BoundaryResize::BottomLeft
             // bottom right resize
-            } else if x < self.p1.0
-                && x > self.p1.0 - 20
-                && y > self.p1.1.saturating_sub(20)
-                && y < self.p1.1
+            } else if x < p1.0
+                && x > p1.0.saturating_sub(20)
+                && y > p1.1.saturating_sub(20)
+                && y < p1.1
             {
                 BoundaryResize::BottomRight
             }
             // bottom resize
-            else if x > self.p0.0
-                && x < self.p1.0
-                && y >= self.p1.1.saturating_sub(10)
-                && y <= self.p1.1 + 10
-            {
+            else if x > p0.0 && x < p1.0 && y >= p1.1.saturating_sub(10) && y <= p1.1 + 10 {
                 BoundaryResize::Bottom
             }
             // left resize
-            else if y > self.p0.1
-                && y < self.p1.1
-                && x >= self.p0.0.saturating_sub(10)
-                && x <= self.p0.0 + 10
-            {
+            else if y > p0.1 && y < p1.1 && x >= p0.0.saturating_sub(10) && x <= p0.0 + 10 {
                 BoundaryResize::Left
             } else {
                 BoundaryResize::None
@@ -814,84 +1661,161 @@ impl Screenshot {
     }
 
     pub fn on_mouse_pressed(&mut self) {
+        self.notice = None;
         if let Some(PhysicalPosition { x, y }) = self.mouse_coordinates {
             let x = x as usize;
             let y = y as usize;
 
+            if let Some(button) = self.what_toolbar_button() {
+                self.press_button(button);
+                return;
+            }
+
+            if let Some(DrawMode::Picker) = self.draw_mode {
+                let (r, g, b, a) = self.pick_color((x, y));
+                if self.modifiers.ctrl() {
+                    self.style.color = BorderColor { r, g, b, a };
+                } else {
+                    self.picked_color = Some(self.color_format.format((r, g, b, a)));
+                }
+                return;
+            }
+
+            if self.draw_mode.is_none() {
+                let grab = self.what_item_grab();
+                self.selected_item = None;
+
+                if let Some((index, grab)) = grab {
+                    self.selected_item = Some(index);
+                    self.item_drag = Some(ItemDrag {
+                        index,
+                        grab,
+                        origin: (x, y),
+                        before: self.drawn_items[index].clone(),
+                    });
+
+                    return;
+                }
+            }
+
             self.boundary_resize_on_press = self.what_resize_opt();
-            if let BoundaryResize::None = self.boundary_resize_on_press {
+            if self.boundary_resize_on_press != BoundaryResize::None {
+                self.selection_on_press = Some(self.selection);
+            } else if let Some(drag) = self.what_selection_drag((x, y)) {
+                self.selection_on_press = Some(self.selection);
+                self.selection_drag = Some(drag);
+            } else {
+                let style = self.style;
                 match self.draw_mode {
                     Some(DrawMode::Arrow) => {
-                        self.drawing_item = Some(DrawnItem::Arrow((x, y), (x, y)));
+                        self.drawing_item = Some(DrawnItem::Arrow((x, y), (x, y), style));
                     }
                     Some(DrawMode::ArrowFilled) => {
-                        self.drawing_item = Some(DrawnItem::ArrowFilled((x, y), (x, y)));
+                        self.drawing_item =
+                            Some(DrawnItem::Arrow((x, y), (x, y), style.filled(true)));
                     }
                     Some(DrawMode::Line) => {
-                        self.drawing_item = Some(DrawnItem::Line((x, y), (x, y)));
+                        self.drawing_item = Some(DrawnItem::Line((x, y), (x, y), style));
                     }
                     Some(DrawMode::RectBorder) => {
-                        self.drawing_item = Some(DrawnItem::RectBorder((x, y), (x, y)));
+                        self.drawing_item = Some(DrawnItem::Rect((x, y), (x, y), style));
+                    }
+                    Some(DrawMode::Ellipse) => {
+                        self.drawing_item = Some(DrawnItem::Ellipse((x, y), (x, y), style));
+                    }
+                    Some(DrawMode::Step) => {
+                        self.drawing_item = Some(DrawnItem::Step((x, y), style));
+                    }
+                    Some(DrawMode::Path) => {
+                        self.drawing_item = Some(DrawnItem::Path(vec![(x, y)], style));
+                    }
+                    Some(DrawMode::Highlight) => {
+                        self.drawing_item = Some(DrawnItem::Highlight((x, y), (x, y), style));
                     }
                     Some(DrawMode::RectBlurred) => {
-                        self.drawing_item = Some(DrawnItem::RectBlurred((x, y), (x, y)));
+                        let redaction = Redaction::Blur {
+                            radius: self.config.blur_radius,
+                            kernel: BlurKernel::Gaussian,
+                        };
+                        self.drawing_item = Some(DrawnItem::Redact((x, y), (x, y), redaction));
+                    }
+                    Some(DrawMode::RectPixelated) => {
+                        let redaction = Redaction::Pixelate {
+                            block_size: self.config.pixelate_block_size,
+                        };
+                        self.drawing_item = Some(DrawnItem::Redact((x, y), (x, y), redaction));
+                    }
+                    Some(DrawMode::RectSecure) => {
+                        self.drawing_item =
+                            Some(DrawnItem::Redact((x, y), (x, y), Redaction::secure()));
                     }
                     Some(DrawMode::Text) => {
-                        self.drawing_item = Some(DrawnItem::Text((
-                            Default::default(),
-                            "".to_string(),
-                            (x, y),
-                        )));
+                        self.text_history.clear();
+                        self.drawing_item = Some(DrawnItem::Text(
+                            (Default::default(), "".to_string(), (x, y)),
+                            style,
+                        ));
                     }
                     Some(DrawMode::RectFilled) => {
-                        self.drawing_item = Some(DrawnItem::RectFilled((x, y), (x, y)));
+                        self.drawing_item =
+                            Some(DrawnItem::Rect((x, y), (x, y), style.filled(true)));
                     }
-                    None => {}
+                    Some(DrawMode::Picker) | None => {}
                 };
             }
         }
     }
 
+    /// Records the move or resize of the dragged item, so an undo in the middle of a drag reverts
+    /// it instead of leaving the drag pointing at an item which is gone.
+    fn finish_item_drag(&mut self) {
+        if let Some(ItemDrag { index, before, .. }) = self.item_drag.take() {
+            let after = self.drawn_items[index].clone();
+            if before != after {
+                self.history.record(Edit::Replace {
+                    index,
+                    before,
+                    after,
+                });
+            }
+        }
+    }
+
     pub fn on_mouse_released(&mut self) {
         self.boundary_resize_on_press = BoundaryResize::None;
+        self.finish_item_drag();
+
+        if let Some(SelectionDrag::Create { .. }) = self.selection_drag.take() {
+            // a click without dragging keeps the previous selection
+            if self.selection.is_empty() {
+                if let Some(before) = self.selection_on_press {
+                    self.selection = before;
+                }
+            }
+        }
+
+        if let Some(before) = self.selection_on_press.take() {
+            let after = self.selection;
+            if before != after {
+                self.history.record(Edit::Selection { before, after });
+            }
+        }
 
         if let (Some(item), Some(PhysicalPosition { x, y })) =
             (&self.drawing_item, self.mouse_coordinates)
         {
-            let (x, y) = (x as usize, y as usize);
-            match (&self.draw_mode, item) {
-                (Some(DrawMode::Arrow), DrawnItem::Arrow(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::Arrow(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
-                }
-                (Some(DrawMode::ArrowFilled), DrawnItem::ArrowFilled(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::ArrowFilled(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
-                }
-                (Some(DrawMode::Line), DrawnItem::Line(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::Line(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
-                }
-                (Some(DrawMode::RectBorder), DrawnItem::RectBorder(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::RectBorder(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
-                }
-                (Some(DrawMode::RectFilled), DrawnItem::RectFilled(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::RectFilled(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
-                }
-                (Some(DrawMode::RectBlurred), DrawnItem::RectBlurred(p0, _)) => {
-                    self.drawn_items.push(DrawnItem::RectBlurred(*p0, (x, y)));
-                    self.drawing_item = None;
-                    self.draw_mode = None;
+            if !matches!(item, DrawnItem::Text(..)) {
+                self.drag_drawing_item((x as usize, y as usize));
+                match self.drawing_item.take() {
+                    Some(DrawnItem::Path(points, style)) => {
+                        let points = simplify(&points, SIMPLIFY_TOLERANCE);
+                        self.commit(Edit::Push(DrawnItem::Path(points, style)));
+                    }
+                    Some(item) => self.commit(Edit::Push(item)),
+                    None => {}
                 }
-                (Some(DrawMode::Text), DrawnItem::Text(..)) => {}
-                _ => {
+                // step markers are placed one per click until the tool is switched off
+                if !matches!(self.draw_mode, Some(DrawMode::Step)) {
                     self.draw_mode = None;
                 }
             }
@@ -899,28 +1823,72 @@ impl Screenshot {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum DrawMode {
     Arrow,
     ArrowFilled,
     Line,
     RectBorder,
     RectFilled,
+    Ellipse,
+    Step,
+    Path,
+    Highlight,
     RectBlurred,
+    RectPixelated,
+    RectSecure,
     Text,
+    /// Picks a color of the original screenshot.
+    Picker,
 }
 
-#[derive(Clone, PartialEq, Eq)]
+impl DrawMode {
+    fn name(&self) -> &'static str {
+        match self {
+            DrawMode::Arrow => "arrow",
+            DrawMode::ArrowFilled => "filled arrow",
+            DrawMode::Line => "line",
+            DrawMode::RectBorder => "rectangle",
+            DrawMode::RectFilled => "filled rectangle",
+            DrawMode::Ellipse => "ellipse",
+            DrawMode::Step => "step",
+            DrawMode::Path => "freehand",
+            DrawMode::Highlight => "highlight",
+            DrawMode::RectBlurred => "blur",
+            DrawMode::RectPixelated => "pixelate",
+            DrawMode::RectSecure => "secure redaction",
+            DrawMode::Text => "text",
+            DrawMode::Picker => "picker",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 enum DrawnItem {
-    Arrow(Pos2, Pos2),
-    ArrowFilled(Pos2, Pos2),
-    Line(Pos2, Pos2),
-    RectBorder(Pos2, Pos2),
-    RectFilled(Pos2, Pos2),
-    RectBlurred(Pos2, Pos2),
-    Text((Cursor, String, Pos2)),
+    Arrow(Pos2, Pos2, Style),
+    Line(Pos2, Pos2, Style),
+    Rect(Pos2, Pos2, Style),
+    Ellipse(Pos2, Pos2, Style),
+    Step(Pos2, Style),
+    Path(Vec<Pos2>, Style),
+    Highlight(Pos2, Pos2, Style),
+    Redact(Pos2, Pos2, Redaction),
+    Text((Cursor, String, Pos2), Style),
+}
+
+enum SelectionDrag {
+    Create { anchor: Pos2 },
+    Move { origin: Pos2, selection: Rect },
+}
+
+struct ItemDrag {
+    index: usize,
+    grab: Grab,
+    origin: Pos2,
+    before: DrawnItem,
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Copy, PartialEq)]
 enum BoundaryResize {
     None,
     Top,
@@ -932,3 +1900,1163 @@ enum BoundaryResize {
     BottomRight,
     Left,
 }
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{blur::DEFAULT_RADIUS, redact::DEFAULT_BLOCK_SIZE};
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+    const STYLE: Style = Style {
+        color: BorderColor::rgb(255, 0, 255),
+        stroke_width: style::DEFAULT_STROKE_WIDTH,
+        opacity: 255,
+        fill: false,
+    };
+    const FILLED: Style = Style {
+        fill: true,
+        ..STYLE
+    };
+    const BLURRED: Redaction = Redaction::Blur {
+        radius: DEFAULT_RADIUS,
+        kernel: BlurKernel::Gaussian,
+    };
+
+    fn screenshot() -> Screenshot {
+        let mut screenshot = Screenshot::new(
+            vec![0; WIDTH * HEIGHT * 4],
+            WIDTH,
+            HEIGHT,
+            Config::default(),
+        );
+        // the toolbar would cover most of such a small screen
+        screenshot.show_toolbar = false;
+
+        screenshot
+    }
+
+    fn area(p0: Pos2, p1: Pos2) -> Rect {
+        Rect::new(p0, p1, (WIDTH, HEIGHT))
+    }
+
+    fn drag(screenshot: &mut Screenshot, from: Pos2, to: Pos2) {
+        screenshot.on_mouse_move(PhysicalPosition::new(from.0 as f64, from.1 as f64));
+        screenshot.on_mouse_pressed();
+        screenshot.on_mouse_move(PhysicalPosition::new(to.0 as f64, to.1 as f64));
+        screenshot.on_mouse_released();
+    }
+
+    fn key(code: VirtualKeyCode) -> KeyboardInput {
+        #[allow(deprecated)]
+        KeyboardInput {
+            scancode: 0,
+            state: ElementState::Pressed,
+            virtual_keycode: Some(code),
+            modifiers: ModifiersState::empty(),
+        }
+    }
+
+    #[test]
+    fn undo_redo_item_creation() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Arrow);
+        drag(&mut screenshot, (50, 50), (80, 60));
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (60, 50), (90, 70));
+
+        assert_eq!(screenshot.drawn_items.len(), 2);
+
+        screenshot.undo();
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Arrow((50, 50), (80, 60), STYLE)]
+        );
+
+        screenshot.undo();
+        assert!(screenshot.drawn_items.is_empty());
+
+        screenshot.redo();
+        screenshot.redo();
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![
+                DrawnItem::Arrow((50, 50), (80, 60), STYLE),
+                DrawnItem::Line((60, 50), (90, 70), STYLE)
+            ]
+        );
+    }
+
+    #[test]
+    fn new_change_drops_redo() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::RectBorder);
+        drag(&mut screenshot, (50, 50), (80, 60));
+        screenshot.undo();
+
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (60, 50), (90, 70));
+        screenshot.redo();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Line((60, 50), (90, 70), STYLE)]
+        );
+    }
+
+    #[test]
+    fn undo_redo_fill_toggle() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::RectBorder);
+        drag(&mut screenshot, (50, 50), (80, 60));
+        screenshot.toggle_filling();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Rect((50, 50), (80, 60), FILLED)]
+        );
+
+        screenshot.undo();
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Rect((50, 50), (80, 60), STYLE)]
+        );
+
+        screenshot.redo();
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Rect((50, 50), (80, 60), FILLED)]
+        );
+    }
+
+    #[test]
+    fn undo_redo_selection() {
+        let mut screenshot = screenshot();
+        drag(&mut screenshot, (5, 5), (30, 40));
+
+        assert_eq!(screenshot.selection, area((30, 40), (WIDTH, HEIGHT)));
+
+        screenshot.undo();
+        assert_eq!(screenshot.selection, area((0, 0), (WIDTH, HEIGHT)));
+
+        screenshot.redo();
+        assert_eq!(screenshot.selection, area((30, 40), (WIDTH, HEIGHT)));
+    }
+
+    #[test]
+    fn undo_redo_text_edits() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Text);
+        drag(&mut screenshot, (50, 50), (50, 50));
+
+        screenshot.handle_input_text_keypress(key(VirtualKeyCode::H));
+        screenshot.handle_input_text_keypress(key(VirtualKeyCode::I));
+        screenshot.undo();
+
+        let Some(DrawnItem::Text((_, content, _), _)) = &screenshot.drawing_item else {
+            panic!("text is not being typed");
+        };
+        assert_eq!(content, "h");
+
+        screenshot.redo();
+        screenshot.handle_input_text_keypress(key(VirtualKeyCode::Return));
+
+        let Some(DrawnItem::Text((_, content, _), _)) = screenshot.drawn_items.last() else {
+            panic!("text is not committed");
+        };
+        assert_eq!(content, "hi");
+
+        screenshot.undo();
+        assert!(screenshot.drawn_items.is_empty());
+    }
+
+    #[test]
+    fn move_and_resize_selected_item() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::RectBorder);
+        drag(&mut screenshot, (50, 50), (80, 70));
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (100, 50), (150, 50));
+
+        drag(&mut screenshot, (50, 60), (60, 65));
+        assert_eq!(screenshot.selected_item, Some(0));
+        assert_eq!(
+            screenshot.drawn_items[0],
+            DrawnItem::Rect((60, 55), (90, 75), STYLE)
+        );
+
+        drag(&mut screenshot, (90, 75), (120, 90));
+        assert_eq!(
+            screenshot.drawn_items[0],
+            DrawnItem::Rect((60, 55), (120, 90), STYLE)
+        );
+
+        screenshot.undo();
+        screenshot.undo();
+        assert_eq!(
+            screenshot.drawn_items[0],
+            DrawnItem::Rect((50, 50), (80, 70), STYLE)
+        );
+
+        // undoing in the middle of a drag reverts the drag and ends it
+        screenshot.on_mouse_move(PhysicalPosition::new(50., 60.));
+        screenshot.on_mouse_pressed();
+        screenshot.on_mouse_move(PhysicalPosition::new(70., 60.));
+        screenshot.undo();
+        screenshot.on_mouse_move(PhysicalPosition::new(90., 60.));
+        screenshot.on_mouse_released();
+        assert_eq!(
+            screenshot.drawn_items[0],
+            DrawnItem::Rect((50, 50), (80, 70), STYLE)
+        );
+        screenshot.undo();
+        assert_eq!(screenshot.drawn_items.len(), 1);
+    }
+
+    #[test]
+    fn toggle_filling_selected_item() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Arrow);
+        drag(&mut screenshot, (50, 50), (80, 60));
+        screenshot.draw_mode = Some(DrawMode::RectBorder);
+        drag(&mut screenshot, (100, 20), (150, 60));
+
+        drag(&mut screenshot, (65, 55), (65, 55));
+        screenshot.toggle_filling();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![
+                DrawnItem::Arrow((50, 50), (80, 60), FILLED),
+                DrawnItem::Rect((100, 20), (150, 60), STYLE)
+            ]
+        );
+    }
+
+    #[test]
+    fn click_on_empty_space_drops_selection() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (50, 50), (80, 50));
+
+        drag(&mut screenshot, (60, 50), (60, 50));
+        assert_eq!(screenshot.selected_item, Some(0));
+
+        drag(&mut screenshot, (60, 90), (60, 90));
+        assert_eq!(screenshot.selected_item, None);
+    }
+
+    #[test]
+    fn items_keep_style_they_were_drawn_with() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Arrow);
+        drag(&mut screenshot, (50, 50), (80, 60));
+
+        screenshot.style.next_color(&style::DEFAULT_PALETTE);
+        screenshot.style.thicker();
+        screenshot.style.more_transparent();
+        screenshot.draw_mode = Some(DrawMode::RectFilled);
+        drag(&mut screenshot, (100, 20), (150, 60));
+
+        let style = Style {
+            color: BorderColor::rgb(255, 255, 0),
+            stroke_width: style::DEFAULT_STROKE_WIDTH + 1,
+            opacity: 204,
+            fill: true,
+        };
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![
+                DrawnItem::Arrow((50, 50), (80, 60), STYLE),
+                DrawnItem::Rect((100, 20), (150, 60), style)
+            ]
+        );
+        assert_eq!(style.rgba(), (255, 255, 0, 204));
+    }
+
+    #[test]
+    fn number_keys_pick_palette_colors() {
+        let mut screenshot = screenshot();
+        let key = |screenshot: &Screenshot, key| {
+            screenshot
+                .config
+                .keys
+                .action_for(key, ModifiersState::empty())
+                .unwrap()
+        };
+
+        screenshot.perform(key(&screenshot, VirtualKeyCode::Key6));
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (50, 50), (80, 60));
+        // Out of the 7 default colors, so the color stays.
+        screenshot.perform(key(&screenshot, VirtualKeyCode::Key9));
+        screenshot.draw_mode = Some(DrawMode::Line);
+        drag(&mut screenshot, (150, 20), (180, 30));
+
+        let black = Style {
+            color: BorderColor::rgb(0, 0, 0),
+            ..STYLE
+        };
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![
+                DrawnItem::Line((50, 50), (80, 60), black),
+                DrawnItem::Line((150, 20), (180, 30), black)
+            ]
+        );
+
+        let help = screenshot.config.keys.help_lines();
+        assert!(help
+            .iter()
+            .any(|(keys, _)| keys == "1 / 2 / 3 / 4 / 5 / 6 / 7 / 8 / 9"));
+    }
+
+    #[test]
+    fn shift_constrains_ellipse_to_circle() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Ellipse);
+        screenshot.modifiers = ModifiersState::SHIFT;
+        drag(&mut screenshot, (100, 50), (130, 40));
+        screenshot.modifiers = ModifiersState::empty();
+        screenshot.toggle_filling();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Ellipse((100, 50), (130, 20), FILLED)]
+        );
+
+        // near the top edge the circle shrinks to the room above
+        screenshot.draw_mode = Some(DrawMode::Ellipse);
+        screenshot.modifiers = ModifiersState::SHIFT;
+        drag(&mut screenshot, (60, 25), (100, 0));
+        assert_eq!(
+            screenshot.drawn_items[1],
+            DrawnItem::Ellipse((60, 25), (85, 0), STYLE)
+        );
+    }
+
+    #[test]
+    fn highlight_keeps_dark_content_readable() {
+        let mut image = vec![255; WIDTH * HEIGHT * 4];
+        let pixel = |x: usize, y: usize| y * WIDTH * 4 + x * 4;
+        image[pixel(100, 50)..pixel(100, 50) + 3].fill(0);
+
+        let config = Config {
+            border_color: BorderColor::rgb(255, 255, 0),
+            ..Config::default()
+        };
+        let mut screenshot = Screenshot::new(image, WIDTH, HEIGHT, config);
+        screenshot.draw_mode = Some(DrawMode::Highlight);
+        drag(&mut screenshot, (60, 50), (140, 50));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+
+        let rgba = |x, y| &screenshot.modified_screenshot[pixel(x, y)..pixel(x, y) + 4];
+        assert_eq!(rgba(100, 50), [0, 0, 0, 255]);
+        assert_eq!(rgba(80, 50), [255, 255, 95, 255]);
+        assert_eq!(rgba(100, 80), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn highlight_shows_on_dark_content() {
+        let mut image = vec![0; WIDTH * HEIGHT * 4];
+        image.chunks_mut(4).for_each(|pixel| pixel[3] = 255);
+        let config = Config {
+            border_color: BorderColor::rgb(255, 255, 0),
+            ..Config::default()
+        };
+        let mut screenshot = Screenshot::new(image, WIDTH, HEIGHT, config);
+        screenshot.draw_mode = Some(DrawMode::Highlight);
+        drag(&mut screenshot, (60, 50), (140, 50));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+
+        let i = 50 * WIDTH * 4 + 80 * 4;
+        assert_eq!(screenshot.modified_screenshot[i..i + 4], [160, 160, 0, 255]);
+    }
+
+    #[test]
+    fn freehand_path_is_simplified() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Path);
+        screenshot.on_mouse_move(PhysicalPosition::new(20., 20.));
+        screenshot.on_mouse_pressed();
+        for (x, y) in [
+            (30, 21),
+            (40, 20),
+            (50, 20),
+            (60, 21),
+            (60, 40),
+            (61, 60),
+            (60, 80),
+        ] {
+            screenshot.on_mouse_move(PhysicalPosition::new(x as f64, y as f64));
+        }
+        screenshot.on_mouse_released();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Path(vec![(20, 20), (60, 21), (60, 80)], STYLE)]
+        );
+
+        screenshot.on_mouse_move(PhysicalPosition::new(61., 50.));
+        screenshot.on_mouse_pressed();
+        screenshot.on_mouse_move(PhysicalPosition::new(71., 55.));
+        screenshot.on_mouse_released();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Path(vec![(30, 25), (70, 26), (70, 85)], STYLE)]
+        );
+    }
+
+    #[test]
+    fn step_markers_renumber_after_delete() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::Step);
+        for x in [40, 100, 160] {
+            drag(&mut screenshot, (x, 50), (x, 50));
+        }
+        screenshot.draw_mode = None;
+        drag(&mut screenshot, (100, 50), (100, 50));
+        screenshot.delete_selected();
+
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![
+                DrawnItem::Step((40, 50), STYLE),
+                DrawnItem::Step((160, 50), STYLE)
+            ]
+        );
+
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+        let mut expected = vec![0; WIDTH * HEIGHT * 4];
+        step::draw_step(&mut expected, (160, 50), 2, WIDTH, true, STYLE.rgba());
+        let marker = |canvas: &[u8]| {
+            (50 - step::RADIUS..50 + step::RADIUS)
+                .map(|y| {
+                    let row = y * WIDTH * 4;
+                    canvas[row + (160 - step::RADIUS) * 4..row + (160 + step::RADIUS) * 4].to_vec()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(marker(&screenshot.modified_screenshot), marker(&expected));
+
+        screenshot.undo();
+        assert_eq!(screenshot.drawn_items.len(), 3);
+        assert_eq!(screenshot.drawn_items[1], DrawnItem::Step((100, 50), STYLE));
+    }
+
+    #[test]
+    fn tab_cycles_redaction_kinds() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::RectBlurred);
+        drag(&mut screenshot, (20, 20), (60, 40));
+
+        let redaction = |screenshot: &Screenshot| match screenshot.drawn_items[..] {
+            [DrawnItem::Redact((20, 20), (60, 40), redaction)] => redaction,
+            _ => panic!("expected a single redaction"),
+        };
+
+        screenshot.toggle_filling();
+        assert_eq!(
+            redaction(&screenshot),
+            Redaction::Pixelate {
+                block_size: DEFAULT_BLOCK_SIZE
+            }
+        );
+        screenshot.toggle_filling();
+        assert_eq!(redaction(&screenshot), Redaction::Solid);
+        screenshot.toggle_filling();
+        assert!(matches!(redaction(&screenshot), Redaction::Secure { .. }));
+        screenshot.toggle_filling();
+        assert_eq!(redaction(&screenshot), BLURRED);
+    }
+
+    /// A synthetic screenshot with text-like high contrast stripes on a gradient.
+    fn synthetic_screenshot() -> Screenshot {
+        let image = (0..WIDTH * HEIGHT)
+            .flat_map(|i| {
+                let (x, y) = (i % WIDTH, i / WIDTH);
+                let ink = if (x / 3 + y / 5) % 2 == 0 { 0 } else { 255 };
+                [ink, x as u8, y as u8, 255]
+            })
+            .collect();
+
+        Screenshot::new(image, WIDTH, HEIGHT, Config::default())
+    }
+
+    fn redact(screenshot: &mut Screenshot, mode: DrawMode, from: Pos2, to: Pos2) {
+        screenshot.draw_mode = Some(mode);
+        drag(screenshot, from, to);
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+    }
+
+    #[test]
+    fn secure_redaction_changes_every_pixel() {
+        let mut screenshot = synthetic_screenshot();
+        redact(&mut screenshot, DrawMode::RectSecure, (30, 20), (170, 80));
+
+        for y in 20..80 {
+            for x in 30..170 {
+                let i = y * WIDTH * 4 + x * 4;
+                assert_ne!(
+                    screenshot.modified_screenshot[i..i + 3],
+                    screenshot.original_screenshot[i..i + 3]
+                );
+            }
+        }
+        assert!(screenshot.check_redactions(&screenshot.get_full_image(), (0, 0)));
+
+        screenshot.selection = area((50, 10), (190, 60));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+        let origin = screenshot.cropped_area().p0();
+        assert!(screenshot.check_redactions(&screenshot.get_cropped_image(), origin));
+    }
+
+    #[test]
+    fn solid_redaction_over_matching_pixels_is_secure() {
+        // the screenshot is black, so every redacted pixel stays equal to the original one
+        let mut screenshot = screenshot();
+        screenshot
+            .drawn_items
+            .push(DrawnItem::Redact((30, 20), (60, 40), Redaction::Solid));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+
+        assert!(screenshot.check_redactions(&screenshot.get_full_image(), (0, 0)));
+    }
+
+    #[test]
+    fn leaking_redaction_refuses_export() {
+        let mut screenshot = synthetic_screenshot();
+        redact(&mut screenshot, DrawMode::RectSecure, (30, 20), (60, 40));
+
+        let mut image = screenshot.get_full_image();
+        let i = 30 * WIDTH * 4 + 45 * 4;
+        image.bytes[i..i + 4].copy_from_slice(&screenshot.original_screenshot[i..i + 4]);
+
+        assert!(!screenshot.check_redactions(&image, (0, 0)));
+        assert_eq!(
+            verify_redaction(
+                &screenshot.original_screenshot,
+                WIDTH,
+                &image,
+                (0, 0),
+                ((30, 20), (60, 40)),
+                Redaction::Solid,
+            ),
+            Err(RedactionIssue::Leaking(1))
+        );
+    }
+
+    #[test]
+    fn noisy_copy_of_original_is_recoverable() {
+        let mut screenshot = synthetic_screenshot();
+        redact(&mut screenshot, DrawMode::RectSecure, (30, 20), (90, 70));
+        let image = screenshot.get_full_image();
+        assert!(screenshot.check_redactions(&image, (0, 0)));
+
+        // every pixel differs from the original, yet the text under it stays readable
+        let mut image = screenshot.get_full_image();
+        for y in 20..70 {
+            for x in 30..90 {
+                let i = y * WIDTH * 4 + x * 4;
+                for c in 0..3 {
+                    let original = screenshot.original_screenshot[i + c];
+                    let noise = ((x * 7 + y * 3) % 20 + 1) as u8;
+                    image.bytes[i + c] = if original > 127 {
+                        original - noise
+                    } else {
+                        original + noise
+                    };
+                }
+            }
+        }
+        assert!(!screenshot.check_redactions(&image, (0, 0)));
+
+        let mut pixels = vec![0; WIDTH * HEIGHT * 4];
+        screenshot.draw(&mut pixels);
+        let notice = screenshot.notice.clone().unwrap();
+        assert!(notice.starts_with("Not saved"), "{notice}");
+        assert_ne!(pixels, {
+            screenshot.notice = None;
+            let mut without = vec![0; WIDTH * HEIGHT * 4];
+            screenshot.draw(&mut without);
+            without
+        });
+    }
+
+    #[test]
+    fn blurred_redaction_only_warns() {
+        let mut screenshot = synthetic_screenshot();
+        redact(&mut screenshot, DrawMode::RectBlurred, (30, 20), (60, 40));
+
+        let image = screenshot.get_full_image();
+        assert_eq!(
+            verify_redaction(
+                &screenshot.original_screenshot,
+                WIDTH,
+                &image,
+                (0, 0),
+                ((30, 20), (60, 40)),
+                BLURRED,
+            ),
+            Err(RedactionIssue::Recoverable)
+        );
+        assert!(screenshot.check_redactions(&image, (0, 0)));
+    }
+
+    #[test]
+    fn keys_adjust_selected_blur() {
+        let mut screenshot = screenshot();
+        screenshot.draw_mode = Some(DrawMode::RectBlurred);
+        drag(&mut screenshot, (20, 20), (60, 40));
+        drag(&mut screenshot, (40, 30), (40, 30));
+
+        screenshot.edit_selected_redaction(Redaction::stronger);
+        screenshot.edit_selected_redaction(Redaction::next_kernel);
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Redact(
+                (20, 20),
+                (60, 40),
+                Redaction::Blur {
+                    radius: DEFAULT_RADIUS + 1,
+                    kernel: BlurKernel::Box
+                }
+            )]
+        );
+
+        screenshot.undo();
+        screenshot.undo();
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Redact((20, 20), (60, 40), BLURRED)]
+        );
+    }
+
+    #[test]
+    fn blur_is_recomputed_when_pixels_under_it_change() {
+        let mut screenshot = synthetic_screenshot();
+        redact(&mut screenshot, DrawMode::RectBlurred, (30, 20), (60, 40));
+        let first = screenshot.modified_screenshot.clone();
+
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+        assert_eq!(screenshot.modified_screenshot, first);
+
+        screenshot
+            .drawn_items
+            .insert(0, DrawnItem::Rect((30, 20), (60, 40), FILLED));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+        let i = 30 * WIDTH * 4 + 45 * 4;
+        assert_eq!(screenshot.modified_screenshot[i..i + 3], [255, 0, 255]);
+    }
+
+    #[test]
+    fn drag_on_empty_space_selects_new_area() {
+        let mut screenshot = screenshot();
+        drag(&mut screenshot, (150, 80), (50, 20));
+        assert_eq!(screenshot.selection, area((50, 20), (150, 80)));
+
+        // a click outside of the selection keeps it
+        drag(&mut screenshot, (180, 90), (180, 90));
+        assert_eq!(screenshot.selection, area((50, 20), (150, 80)));
+
+        screenshot.undo();
+        assert_eq!(screenshot.selection, area((0, 0), (WIDTH, HEIGHT)));
+    }
+
+    #[test]
+    fn selection_crosses_screens_of_different_scales() {
+        // a 1x screen next to a 2x one, as `desktop::layout` places them
+        let captures = [(0, 120, 100), (120, 80, 50)].map(|(x, width, height)| {
+            let image = image::RgbaImage::from_fn(width, height, |x, y| {
+                image::Rgba([x as u8, y as u8, width as u8, 255])
+            });
+            let area = Area {
+                x,
+                y: 0,
+                width,
+                height,
+            };
+            ScreenCapture { area, image }
+        });
+        let desktop = compose(&captures);
+        assert_eq!(desktop.dimensions(), (WIDTH as u32, HEIGHT as u32));
+
+        let mut screenshot = Screenshot::new(desktop.into_raw(), WIDTH, HEIGHT, Config::default());
+        screenshot.show_toolbar = false;
+        drag(&mut screenshot, (100, 20), (160, 40));
+        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+
+        let image = screenshot.get_cropped_image();
+        let row = &image.bytes[..image.width * 4];
+        assert_eq!(row[2], 120);
+        assert_eq!(row[row.len() - 2], 80);
+    }
+
+    #[test]
+    fn drag_inside_selection_moves_it() {
+        let mut screenshot = screenshot();
+        drag(&mut screenshot, (50, 20), (150, 80));
+
+        drag(&mut screenshot, (100, 50), (90, 60));
+        assert_eq!(screenshot.selection, area((40, 30), (140, 90)));
+
+        // the selection stays on the screen
+        drag(&mut screenshot, (100, 50), (190, 0));
+        assert_eq!(screenshot.selection, area((100, 0), (WIDTH, 60)));
+
+        // with an active tool only Ctrl moves the selection
+        screenshot.draw_mode = Some(DrawMode::Line);
+        screenshot.modifiers = ModifiersState::CTRL;
+        drag(&mut screenshot, (150, 30), (140, 40));
+        assert_eq!(screenshot.selection, area((90, 10), (190, 70)));
+        assert!(screenshot.drawn_items.is_empty());
+
+        screenshot.modifiers = ModifiersState::empty();
+        drag(&mut screenshot, (150, 30), (140, 40));
+        assert_eq!(screenshot.selection, area((90, 10), (190, 70)));
+        assert_eq!(screenshot.drawn_items.len(), 1);
+    }
+
+    #[test]
+    fn selection_edges_dragged_past_each_other() {
+        let mut screenshot = screenshot();
+        drag(&mut screenshot, (50, 20), (150, 80));
+
+        screenshot.on_mouse_move(PhysicalPosition::new(50., 50.));
+        screenshot.on_mouse_pressed();
+        screenshot.on_mouse_move(PhysicalPosition::new(170., 50.));
+        assert_eq!(screenshot.selection, area((150, 20), (170, 80)));
+
+        screenshot.on_mouse_move(PhysicalPosition::new(160., 50.));
+        screenshot.on_mouse_released();
+        assert_eq!(screenshot.selection, area((150, 20), (160, 80)));
+    }
+
+    #[test]
+    fn hud_is_shown_next_to_selection() {
+        let mut screenshot = screenshot();
+        let mut pixels = vec![0; WIDTH * HEIGHT * 4];
+        drag(&mut screenshot, (30, 30), (120, 60));
+        screenshot.draw(&mut pixels);
+
+        let row = |pixels: &[u8], y: usize| pixels[y * WIDTH * 4..(y + 1) * WIDTH * 4].to_vec();
+        let changed: Vec<_> = (0..HEIGHT)
+            .filter(|y| row(&pixels, *y) != row(&screenshot.modified_screenshot, *y))
+            .collect();
+        assert_eq!((changed[0], changed[changed.len() - 1]), (66, 87));
+        assert_eq!(
+            hud_text(screenshot.cropped_area(), Some((120, 60)), "select", 3),
+            "86×26 at 32,32  cursor 120,60  select 3px"
+        );
+
+        screenshot.show_hud = false;
+        screenshot.draw(&mut pixels);
+        assert_eq!(pixels, screenshot.modified_screenshot);
+    }
+
+    fn click_button(screenshot: &mut Screenshot, button: Button) {
+        let toolbar = screenshot.toolbar().unwrap();
+        let area = toolbar.area();
+        let pos = (area.p0().0..area.p1().0)
+            .flat_map(|x| (area.p0().1..area.p1().1).map(move |y| (x, y)))
+            .find(|pos| toolbar.hit(*pos) == Some(button))
+            .unwrap();
+
+        drag(screenshot, pos, pos);
+    }
+
+    #[test]
+    fn toolbar_buttons_act_like_hotkeys() {
+        let (width, height) = (640, 360);
+        let mut screenshot = Screenshot::new(
+            vec![0; width * height * 4],
+            width,
+            height,
+            Config::default(),
+        );
+        assert!(screenshot.toolbar().is_none());
+        drag(&mut screenshot, (100, 50), (600, 200));
+
+        click_button(&mut screenshot, Button::Tool(DrawMode::Ellipse));
+        assert_eq!(screenshot.draw_mode, Some(DrawMode::Ellipse));
+        click_button(&mut screenshot, Button::Tool(DrawMode::Ellipse));
+        assert_eq!(screenshot.draw_mode, None);
+
+        // unlike buttons, tool hotkeys only switch to their tool, except for steps
+        screenshot.perform(Action::Tool(DrawMode::Ellipse));
+        screenshot.perform(Action::Tool(DrawMode::Ellipse));
+        assert_eq!(screenshot.draw_mode, Some(DrawMode::Ellipse));
+        screenshot.perform(Action::Tool(DrawMode::Step));
+        assert_eq!(screenshot.draw_mode, Some(DrawMode::Step));
+        screenshot.perform(Action::Tool(DrawMode::Step));
+        assert_eq!(screenshot.draw_mode, None);
+
+        click_button(&mut screenshot, Button::Thicker);
+        click_button(&mut screenshot, Button::Tool(DrawMode::RectBorder));
+        drag(&mut screenshot, (150, 80), (250, 150));
+        assert_eq!(
+            screenshot.drawn_items,
+            vec![DrawnItem::Rect(
+                (150, 80),
+                (250, 150),
+                Style {
+                    stroke_width: 4,
+                    ..STYLE
+                }
+            )]
+        );
+        assert_eq!(
+            screenshot.selection,
+            Rect::new((100, 50), (600, 200), (width, height))
+        );
+
+        click_button(&mut screenshot, Button::Undo);
+        assert!(screenshot.drawn_items.is_empty());
+
+        click_button(&mut screenshot, Button::Save);
+        assert_eq!(screenshot.export_requested, None);
+        assert!(screenshot.notice.is_some());
+
+        click_button(&mut screenshot, Button::Copy);
+        assert_eq!(screenshot.export_requested, Some(Destination::Clipboard));
+        assert!(!screenshot.config.clipboard);
+    }
+
+    #[test]
+    fn toolbar_stays_on_screen_near_edges() {
+        let canvas = (640, 360);
+        let toolbar = |p0, p1| Toolbar::new(Rect::new(p0, p1, canvas), canvas).area();
+
+        let below = toolbar((100, 50), (600, 200));
+        assert_eq!((below.p1().0, below.p0().1), (600, 206));
+
+        let above = toolbar((10, 100), (300, 360));
+        assert_eq!((above.p0().0, above.p1().1), (0, 94));
+
+        let inside = toolbar((0, 0), canvas);
+        assert_eq!((inside.p1().0, inside.p1().1), (640, 354));
+        assert_eq!(inside.width(), below.width());
+
+        let narrow = Toolbar::new(Rect::new((0, 0), (100, 100), (200, 360)), (200, 360));
+        assert!(narrow.area().width() <= 200);
+        assert_eq!(narrow.area().p0().1, 106);
+        assert!(narrow.area().height() > 3 * toolbar::BUTTON_SIZE);
+    }
+
+    #[test]
+    fn help_sheet_is_toggled_and_drawn_over_everything() {
+        let mut screenshot = synthetic_screenshot();
+        let mut pixels = vec![0; WIDTH * HEIGHT * 4];
+        screenshot.draw(&mut pixels);
+        let without_help = pixels.clone();
+
+        let toggle = screenshot
+            .config
+            .keys
+            .action_for(VirtualKeyCode::Slash, ModifiersState::SHIFT)
+            .unwrap();
+        screenshot.perform(toggle);
+        assert!(screenshot.show_help);
+        screenshot.draw(&mut pixels);
+        assert_ne!(pixels, without_help);
+
+        let toggle = screenshot
+            .config
+            .keys
+            .action_for(VirtualKeyCode::F1, ModifiersState::empty());
+        screenshot.perform(toggle.unwrap());
+        assert!(!screenshot.show_help);
+        screenshot.draw(&mut pixels);
+        assert_eq!(pixels, without_help);
+    }
+
+    #[test]
+    fn help_sheet_is_centered_on_one_screen() {
+        let (width, height) = (1200, 400);
+        let mut screenshot = Screenshot::new(
+            vec![0; width * height * 4],
+            width,
+            height,
+            Config::default(),
+        );
+        let half = width / 2;
+        screenshot.screens = vec![
+            Rect::new((0, 0), (half, height), (width, height)),
+            Rect::new((half, 0), (width, height), (width, height)),
+        ];
+        let mut pixels = vec![0; width * height * 4];
+
+        for (x, shown) in [(900., half..width), (300., 0..half)] {
+            screenshot.on_mouse_move(PhysicalPosition::new(x, 50.));
+            screenshot.show_help = false;
+            screenshot.draw(&mut pixels);
+            let without_help = pixels.clone();
+
+            screenshot.show_help = true;
+            screenshot.draw(&mut pixels);
+            for (i, (pixel, before)) in pixels.chunks(4).zip(without_help.chunks(4)).enumerate() {
+                assert!(pixel == before || shown.contains(&(i % width)), "{x} {i}");
+            }
+            assert_ne!(pixels, without_help);
+        }
+    }
+
+    #[test]
+    fn loupe_magnifies_pixels_under_cursor() {
+        let mut screenshot = synthetic_screenshot();
+        let mut pixels = vec![0; WIDTH * HEIGHT * 4];
+        screenshot.show_hud = false;
+        screenshot.on_mouse_move(PhysicalPosition::new(60.5, 30.5));
+        screenshot.draw(&mut pixels);
+        assert_eq!(pixels, screenshot.modified_screenshot);
+
+        // only shown once a shape is being placed
+        screenshot.draw_mode = Some(DrawMode::Line);
+        screenshot.draw(&mut pixels);
+        assert_eq!(pixels, screenshot.modified_screenshot);
+        screenshot.on_mouse_pressed();
+        screenshot.draw(&mut pixels);
+
+        let (left, top) = loupe::loupe_origin((60, 30), WIDTH, HEIGHT);
+        for (dx, dy) in [(0, 0), (3, -2), (-7, 3)] {
+            let x = left + (loupe::REGION / 2).saturating_add_signed(dx) * loupe::ZOOM + 4;
+            let y = top + (loupe::REGION / 2).saturating_add_signed(dy) * loupe::ZOOM + 4;
+            let i = y * WIDTH * 4 + x * 4;
+            let j = (30 + dy) as usize * WIDTH * 4 + (60 + dx) as usize * 4;
+            assert_eq!(pixels[i..i + 3], screenshot.original_screenshot[j..j + 3]);
+        }
+        let border: (u8, u8, u8, u8) = screenshot.config.border_color.into();
+        let middle = (loupe::REGION / 2) * loupe::ZOOM + loupe::ZOOM / 2;
+        for (x, y) in [
+            (left + middle - loupe::ZOOM, top + middle),
+            (left + middle, top + middle - loupe::ZOOM),
+        ] {
+            let i = y * WIDTH * 4 + x * 4;
+            assert_eq!(pixels[i..i + 3], [border.0, border.1, border.2]);
+        }
+        assert_eq!(loupe::pixel_label((255, 0, 128, 255)), "255,0,128 #ff0080");
+    }
+
+    #[test]
+    fn picker_copies_or_applies_original_colors() {
+        let mut screenshot = synthetic_screenshot();
+        let picker = screenshot
+            .config
+            .keys
+            .action_for(VirtualKeyCode::G, ModifiersState::empty());
+        screenshot.perform(picker.unwrap());
+        assert_eq!(screenshot.draw_mode, Some(DrawMode::Picker));
+        drag(&mut screenshot, (40, 20), (40, 20));
+        assert_eq!(screenshot.picked_color.take().as_deref(), Some("#ff2814"));
+        assert!(screenshot.drawn_items.is_empty());
+
+        screenshot.perform(Action::ToggleFill);
+        screenshot.modifiers = ModifiersState::SHIFT;
+        drag(&mut screenshot, (1, 0), (1, 0));
+        assert_eq!(
+            screenshot.picked_color.take().as_deref(),
+            Some("rgb(0, 1, 0)")
+        );
+
+        screenshot.perform(Action::ToggleFill);
+        screenshot.modifiers = ModifiersState::empty();
+        drag(&mut screenshot, (3, 0), (3, 0));
+        assert_eq!(
+            screenshot.picked_color.take().as_deref(),
+            Some("255,3,0,255")
+        );
+        assert_eq!("255,3,0,255".parse(), Ok(BorderColor::rgb(255, 3, 0)));
+
+        screenshot.modifiers = ModifiersState::CTRL;
+        drag(&mut screenshot, (120, 60), (150, 80));
+        assert_eq!(screenshot.picked_color, None);
+        assert_eq!(screenshot.style.color, BorderColor::rgb(0, 120, 60));
+        assert_eq!(screenshot.selection, Rect::full(WIDTH, HEIGHT));
+    }
+
+    #[test]
+    fn config_file_is_overridden_by_flags() {
+        let config = Config::parse(
+            "dir = \"/tmp/shots\"\nblur_radius = 8\npalette = [\"0,0,0,255\"]\nborder_width = 4\n",
+        )
+        .unwrap();
+        assert_eq!(config.blur_radius, 8);
+        assert_eq!(config.palette, vec![BorderColor::rgb(0, 0, 0)]);
+        assert_eq!(config.pixelate_block_size, DEFAULT_BLOCK_SIZE);
+        assert_eq!(Config::parse(&config.to_toml()), Ok(config.clone()));
+
+        let args = BirdyArgs::try_parse_from(["birdy", "-c", "--blur-radius", "3"]).unwrap();
+        let merged = args.apply(config.clone());
+        assert_eq!((merged.clipboard, merged.blur_radius), (true, 3));
+        assert_eq!((merged.dir, merged.border_width), (config.dir, 4));
+
+        let clipboard = Config {
+            clipboard: true,
+            ..Config::default()
+        };
+        let args = BirdyArgs::try_parse_from(["birdy", "-d", "/tmp"]).unwrap();
+        let merged = args.apply(clipboard);
+        assert_eq!((merged.clipboard, merged.dir), (true, Some("/tmp".into())));
+
+        let args = BirdyArgs::try_parse_from(["birdy", "config", "print"]).unwrap();
+        assert_eq!(args.apply(Config::default()), Config::default());
+
+        assert!(Config::parse("stroke_width = 0").is_err());
+        assert!(Config::parse("border_width = 0").is_err());
+        assert!(Config::parse("border_width = 1000").is_err());
+        assert!(Config::parse("blur_radius = 0").is_err());
+        assert!(Config::parse("pixelate_block_size = 65").is_err());
+        let args = BirdyArgs::try_parse_from(["birdy", "--blur-radius", "51"]).unwrap();
+        assert!(args.apply(Config::default()).validate().is_err());
+        assert!(Config::parse("palette = []").is_err());
+        assert!(Config::parse("border_color = \"1,2\"").is_err());
+        assert!(Config::parse("unknown = 1").is_err());
+    }
+
+    /// Input which the overlay can receive, including positions outside of the window.
+    #[derive(Clone, Debug)]
+    enum Input {
+        Move(f64, f64),
+        Press,
+        Release,
+        Tool(usize),
+        Ctrl(bool),
+    }
+
+    fn tool(index: usize) -> Option<DrawMode> {
+        [
+            DrawMode::Arrow,
+            DrawMode::ArrowFilled,
+            DrawMode::Line,
+            DrawMode::RectBorder,
+            DrawMode::RectFilled,
+            DrawMode::Ellipse,
+            DrawMode::Step,
+            DrawMode::Path,
+            DrawMode::Highlight,
+            DrawMode::RectBlurred,
+            DrawMode::RectPixelated,
+            DrawMode::RectSecure,
+            DrawMode::Text,
+            DrawMode::Picker,
+        ]
+        .into_iter()
+        .nth(index)
+    }
+
+    /// A coordinate before, around or past the end of a canvas side `size` long.
+    fn straddling(size: usize) -> impl Strategy<Value = usize> {
+        prop_oneof![0..20usize, size - 20..size + 20, 0..size * 3]
+    }
+
+    fn input() -> impl Strategy<Value = Input> {
+        prop_oneof![
+            4 => (-50. ..WIDTH as f64 + 50., -50. ..HEIGHT as f64 + 50.)
+                .prop_map(|(x, y)| Input::Move(x, y)),
+            2 => Just(Input::Press),
+            2 => Just(Input::Release),
+            1 => (0..15usize).prop_map(Input::Tool),
+            1 => any::<bool>().prop_map(Input::Ctrl),
+        ]
+    }
+
+    proptest! {
+        // every case replays a whole session of drawing, which is slow in debug builds
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn mouse_input_never_panics(
+            inputs in prop::collection::vec(input(), 1..40),
+            antialiasing in any::<bool>(),
+        ) {
+            let mut screenshot = synthetic_screenshot();
+            screenshot.config.antialiasing = antialiasing;
+
+            for input in inputs {
+                match input {
+                    Input::Move(x, y) => screenshot.on_mouse_move(PhysicalPosition::new(x, y)),
+                    Input::Press => screenshot.on_mouse_pressed(),
+                    Input::Release => {
+                        screenshot.on_mouse_released();
+                        screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+                    }
+                    Input::Tool(index) => screenshot.draw_mode = tool(index),
+                    Input::Ctrl(ctrl) => screenshot.modifiers.set(ModifiersState::CTRL, ctrl),
+                }
+
+                let (p0, p1) = (screenshot.selection.p0(), screenshot.selection.p1());
+                prop_assert!(p0.0 <= p1.0 && p0.1 <= p1.1);
+                prop_assert!(p1.0 <= WIDTH && p1.1 <= HEIGHT);
+            }
+
+            screenshot.draw(&mut vec![0; WIDTH * HEIGHT * 4]);
+            let image = screenshot.get_cropped_image();
+            prop_assert_eq!(image.bytes.len(), image.width * image.height * 4);
+        }
+
+        #[test]
+        fn primitives_only_draw_within_their_bounds(
+            kind in 0..12usize,
+            a in (straddling(WIDTH), straddling(HEIGHT)),
+            b in (straddling(WIDTH), straddling(HEIGHT)),
+            t in prop_oneof![1..=style::MAX_STROKE_WIDTH, 1..1000usize],
+            antialiasing in any::<bool>(),
+        ) {
+            let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+            let original = canvas.clone();
+            let color = STYLE.rgba();
+            let w = WIDTH;
+
+            match kind {
+                0 => draw_arrow_bordered(&mut canvas, a.0, a.1, b.0, b.1, w, t, antialiasing, color),
+                1 => draw_arrow_filled(&mut canvas, a.0, a.1, b.0, b.1, w, t, antialiasing, color),
+                2 => draw_line_thick(&mut canvas, a.0, a.1, b.0, b.1, w, t, antialiasing, color),
+                3 => draw_rect_bordered(&mut canvas, a.0, a.1, b.0, b.1, w, t, antialiasing, color),
+                4 => draw_rect_filled(&mut canvas, a.0, a.1, b.0, b.1, w, color),
+                5 => draw_ellipse_filled(&mut canvas, a.0, a.1, b.0, b.1, w, antialiasing, color),
+                6 => triangle::draw_triangle_filled(&mut canvas, a, b, (a.0, b.1), w, color),
+                7 => draw_step(&mut canvas, a, 7, w, antialiasing, color),
+                8 => draw_path(&mut canvas, &[a, b], w, t, antialiasing, color),
+                9 => draw_highlight_stroke(&mut canvas, a.0, a.1, b.0, b.1, w, t, antialiasing, color),
+                10 => draw_highlight_filled(&mut canvas, a.0, a.1, b.0, b.1, w, color),
+                _ => draw_redaction(
+                    &mut canvas,
+                    &original,
+                    &mut BlurCache::default(),
+                    a.0,
+                    a.1,
+                    b.0,
+                    b.1,
+                    w,
+                    Redaction::secure(),
+                ),
+            }
+
+            // how far each primitive may reach past the rectangle between its points, arrow heads
+            // are 50 px long
+            let reach = match kind {
+                0 | 1 => t + 50,
+                2 | 3 | 8 => t,
+                7 => step::RADIUS + 1,
+                9 => t * highlight::WIDTH_SCALE / 2,
+                _ => 1,
+            };
+
+            let bounds = (a.0.min(b.0), a.1.min(b.1), a.0.max(b.0), a.1.max(b.1));
+            for (i, (pixel, before)) in canvas.chunks(4).zip(original.chunks(4)).enumerate() {
+                let (x, y) = (i % WIDTH, i / WIDTH);
+                prop_assert!(
+                    pixel == before
+                        || (x + reach >= bounds.0
+                            && y + reach >= bounds.1
+                            && x <= bounds.2 + reach
+                            && y <= bounds.3 + reach),
+                    "{kind} drew at {x},{y}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    blend::BlendMode,
+    stroke::{draw_polyline, Cap, Join, Stroke},
+    Pos2,
+};
+
+/// Points closer than this to the simplified path are dropped.
+pub const SIMPLIFY_TOLERANCE: f64 = 1.5;
+const SMOOTHING_ITERATIONS: usize = 2;
+
+/// Draws a freehand path as a thick polyline with rounded corners.
+pub fn draw_path(
+    canvas: &mut [u8],
+    points: &[Pos2],
+    width: usize,
+    thickness: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    let points: Vec<_> = points.iter().map(|p| (p.0 as f64, p.1 as f64)).collect();
+
+    draw_polyline(
+        canvas,
+        width,
+        &smooth(&points),
+        false,
+        Stroke {
+            width: thickness.max(1) as f64,
+            cap: Cap::Round,
+            join: Join::Round,
+        },
+        antialiasing,
+        color,
+        BlendMode::SourceOver,
+    );
+}
+
+/// Ramer–Douglas–Peucker: keeps only the points which deviate from the straight line between
+/// their neighbours by more than `tolerance`.
+pub fn simplify(points: &[Pos2], tolerance: f64) -> Vec<Pos2> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let (farthest, distance) = points[1..points.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (i + 1, distance_to_line(*p, first, last)))
+        .fold((0, 0.), |max, d| if d.1 > max.1 { d } else { max });
+
+    if distance <= tolerance {
+        return vec![first, last];
+    }
+
+    let mut simplified = simplify(&points[..=farthest], tolerance);
+    simplified.pop();
+    simplified.extend(simplify(&points[farthest..], tolerance));
+
+    simplified
+}
+
+/// Chaikin's corner cutting, which keeps the endpoints in place.
+fn smooth(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut points = points.to_vec();
+
+    for _ in 0..SMOOTHING_ITERATIONS {
+        if points.len() < 3 {
+            break;
+        }
+
+        let mut smoothed = vec![points[0]];
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            smoothed.push((0.75 * a.0 + 0.25 * b.0, 0.75 * a.1 + 0.25 * b.1));
+            smoothed.push((0.25 * a.0 + 0.75 * b.0, 0.25 * a.1 + 0.75 * b.1));
+        }
+        smoothed.push(points[points.len() - 1]);
+
+        points = smoothed;
+    }
+
+    points
+}
+
+fn distance_to_line(p: Pos2, a: Pos2, b: Pos2) -> f64 {
+    let (px, py) = (p.0 as f64, p.1 as f64);
+    let (ax, ay) = (a.0 as f64, a.1 as f64);
+    let (bx, by) = (b.0 as f64, b.1 as f64);
+
+    let length = ((bx - ax).powi(2) + (by - ay).powi(2)).sqrt();
+    if length == 0. {
+        return ((px - ax).powi(2) + (py - ay).powi(2)).sqrt();
+    }
+
+    ((bx - ax) * (ay - py) - (ax - px) * (by - ay)).abs() / length
+}
@@ -0,0 +1,53 @@
+use crate::Pos2;
+
+/// How a picked color is copied to the clipboard.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorFormat {
+    #[default]
+    Hex,
+    Rgb,
+    /// Comma separated values, the format `--border-color` accepts.
+    Components,
+}
+
+impl ColorFormat {
+    pub fn next(self) -> Self {
+        match self {
+            ColorFormat::Hex => ColorFormat::Rgb,
+            ColorFormat::Rgb => ColorFormat::Components,
+            ColorFormat::Components => ColorFormat::Hex,
+        }
+    }
+
+    pub fn format(self, (r, g, b, a): (u8, u8, u8, u8)) -> String {
+        match self {
+            ColorFormat::Hex => format!("#{r:02x}{g:02x}{b:02x}"),
+            ColorFormat::Rgb => format!("rgb({r}, {g}, {b})"),
+            ColorFormat::Components => format!("{r},{g},{b},{a}"),
+        }
+    }
+}
+
+/// Color of the pixel of `source` at `pos`, or the average of the 3×3 pixels around it. Pixels
+/// outside of the canvas are left out of the average.
+pub fn pick_color(source: &[u8], width: usize, pos: Pos2, averaged: bool) -> (u8, u8, u8, u8) {
+    let height = source.len() / (width * 4).max(1);
+    let reach = usize::from(averaged);
+    let rows = pos.1.saturating_sub(reach)..(pos.1 + reach + 1).min(height);
+    let columns = pos.0.saturating_sub(reach)..(pos.0 + reach + 1).min(width);
+
+    let mut sum = [0usize; 4];
+    let mut count = 0;
+    for y in rows {
+        for x in columns.clone() {
+            let i = y * (width * 4) + (x * 4);
+            for (total, channel) in sum.iter_mut().zip(&source[i..i + 4]) {
+                *total += *channel as usize;
+            }
+            count += 1;
+        }
+    }
+
+    let [r, g, b, a] = sum.map(|channel| (channel / count.max(1)) as u8);
+    (r, g, b, a)
+}
@@ -0,0 +1,123 @@
+use crate::Pos2;
+
+/// An area of the canvas whose `p0` is always the top left corner and whose corners never lie
+/// outside of the canvas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    p0: Pos2,
+    p1: Pos2,
+}
+
+impl Rect {
+    /// Spans the rectangle between any two corners, clamped to a canvas of the given size.
+    pub fn new(a: Pos2, b: Pos2, (width, height): Pos2) -> Self {
+        let clamp = |p: Pos2| (p.0.min(width), p.1.min(height));
+        let (a, b) = (clamp(a), clamp(b));
+
+        Self {
+            p0: (a.0.min(b.0), a.1.min(b.1)),
+            p1: (a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    pub fn full(width: usize, height: usize) -> Self {
+        Self {
+            p0: (0, 0),
+            p1: (width, height),
+        }
+    }
+
+    pub fn p0(&self) -> Pos2 {
+        self.p0
+    }
+
+    pub fn p1(&self) -> Pos2 {
+        self.p1
+    }
+
+    pub fn width(&self) -> usize {
+        self.p1.0 - self.p0.0
+    }
+
+    pub fn height(&self) -> usize {
+        self.p1.1 - self.p0.1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Whether `pos` lies strictly inside, not on the edges.
+    pub fn contains(&self, pos: Pos2) -> bool {
+        pos.0 > self.p0.0 && pos.0 < self.p1.0 && pos.1 > self.p0.1 && pos.1 < self.p1.1
+    }
+
+    /// Moves the rectangle by (`dx`, `dy`), stopping at the canvas edges without shrinking.
+    pub fn translate(&self, dx: isize, dy: isize, (width, height): Pos2) -> Self {
+        let shift = |from: usize, delta: isize, size: usize, max: usize| {
+            from.saturating_add_signed(delta)
+                .min(max.saturating_sub(size))
+        };
+        let p0 = (
+            shift(self.p0.0, dx, self.width(), width),
+            shift(self.p0.1, dy, self.height(), height),
+        );
+
+        Self::new(
+            p0,
+            (p0.0 + self.width(), p0.1 + self.height()),
+            (width, height),
+        )
+    }
+
+    /// The smallest rectangle covering both.
+    pub fn union(&self, other: &Rect) -> Self {
+        Self {
+            p0: (self.p0.0.min(other.p0.0), self.p0.1.min(other.p0.1)),
+            p1: (self.p1.0.max(other.p1.0), self.p1.1.max(other.p1.1)),
+        }
+    }
+
+    /// Shrinks every edge by `by` pixels. A rectangle too small for that becomes empty.
+    pub fn inset(&self, by: usize) -> Self {
+        let p0 = (
+            (self.p0.0 + by).min(self.p1.0),
+            (self.p0.1 + by).min(self.p1.1),
+        );
+        let p1 = (
+            self.p1.0.saturating_sub(by).max(p0.0),
+            self.p1.1.saturating_sub(by).max(p0.1),
+        );
+
+        Self { p0, p1 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    proptest! {
+        #[test]
+        fn rect_stays_normalized_and_on_the_canvas(
+            a in (0..WIDTH * 2, 0..HEIGHT * 2),
+            b in (0..WIDTH * 2, 0..HEIGHT * 2),
+            (dx, dy) in (-400..400isize, -400..400isize),
+            by in 0..60usize,
+        ) {
+            let rect = Rect::new(a, b, (WIDTH, HEIGHT));
+            for rect in [rect, rect.translate(dx, dy, (WIDTH, HEIGHT)), rect.inset(by)] {
+                prop_assert!(rect.p0().0 <= rect.p1().0 && rect.p0().1 <= rect.p1().1);
+                prop_assert!(rect.p1().0 <= WIDTH && rect.p1().1 <= HEIGHT);
+            }
+
+            let moved = rect.translate(dx, dy, (WIDTH, HEIGHT));
+            prop_assert_eq!((moved.width(), moved.height()), (rect.width(), rect.height()));
+        }
+    }
+}
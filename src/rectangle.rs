@@ -1,3 +1,10 @@
+use crate::{
+    blend::{blend_pixel, BlendMode},
+    stroke::{draw_polyline, Cap, Join, Stroke},
+};
+
+/// Draws a `thickness` pixels wide border which lies inside the given rectangle.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_rect_bordered(
     canvas: &mut [u8],
     x0: usize,
@@ -5,19 +12,31 @@ pub fn draw_rect_bordered(
     x1: usize,
     y1: usize,
     width: usize,
+    thickness: usize,
+    antialiasing: bool,
     color: (u8, u8, u8, u8),
 ) {
-    // top
-    draw_rect_filled(canvas, x0, y0, x1, y0 + 2, width, color);
-
-    // right
-    draw_rect_filled(canvas, x1.saturating_sub(2), y0, x1, y1, width, color);
+    let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
+    let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
 
-    // bottom
-    draw_rect_filled(canvas, x0, y1, x1, y1.saturating_sub(2), width, color);
+    let inset = thickness as f64 / 2.;
+    let (left, top) = (x0 as f64 + inset, y0 as f64 + inset);
+    let (right, bottom) = ((x1 as f64 - inset).max(left), (y1 as f64 - inset).max(top));
 
-    // left
-    draw_rect_filled(canvas, x0, y0, x0 + 2, y1, width, color);
+    draw_polyline(
+        canvas,
+        width,
+        &[(left, top), (right, top), (right, bottom), (left, bottom)],
+        true,
+        Stroke {
+            width: thickness as f64,
+            cap: Cap::Square,
+            join: Join::Miter,
+        },
+        antialiasing,
+        color,
+        BlendMode::SourceOver,
+    );
 }
 
 pub fn draw_rect_filled(
@@ -29,25 +48,29 @@ pub fn draw_rect_filled(
     width: usize,
     color: (u8, u8, u8, u8),
 ) {
-    let red = color.0;
-    let green = color.1;
-    let blue = color.2;
-    let alpha = color.3;
+    draw_rect_filled_blended(canvas, x0, y0, x1, y1, width, color, BlendMode::SourceOver);
+}
 
+/// Fills the rectangle compositing `color` over the canvas with the given blend mode.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_rect_filled_blended(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    color: (u8, u8, u8, u8),
+    mode: BlendMode,
+) {
     let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
     let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
+    let height = canvas.len() / (width * 4).max(1);
+    let (x1, y1) = (x1.min(width), y1.min(height));
 
     for hh in y0..y1 {
         for ww in x0..x1 {
-            if (hh >= y0 && hh <= y1)
-                && (ww >= x0 && ww <= x1)
-                && canvas.get(hh * (width * 4) + (ww * 4) + 3).is_some()
-            {
-                canvas[hh * (width * 4) + (ww * 4)] = red;
-                canvas[hh * (width * 4) + (ww * 4) + 1] = green;
-                canvas[hh * (width * 4) + (ww * 4) + 2] = blue;
-                canvas[hh * (width * 4) + (ww * 4) + 3] = alpha;
-            }
+            blend_pixel(canvas, hh * (width * 4) + (ww * 4), color, 1., mode);
         }
     }
 }
@@ -0,0 +1,341 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    blend::{blend_pixel, BlendMode},
+    blur::{draw_rect_blurred, BlurCache, BlurKernel, MAX_RADIUS},
+    rectangle::draw_rect_filled,
+    Image, Pos2,
+};
+
+pub const DEFAULT_BLOCK_SIZE: usize = 10;
+pub const MIN_BLOCK_SIZE: usize = 2;
+pub const MAX_BLOCK_SIZE: usize = 64;
+const SOLID_COLOR: (u8, u8, u8, u8) = (0, 0, 0, 255);
+/// Block size of secure redactions, coarse enough that no glyph survives.
+const SECURE_BLOCK_SIZE: usize = 24;
+/// Maximum noise added to every channel of a secure redaction.
+const SECURE_NOISE: i32 = 48;
+/// How closely the detail inside a block of an exported redaction may follow the original. Solid
+/// fills and coarse pixelation leave no detail and the noise doesn't depend on the original, so
+/// intact redactions stay close to 0.
+const MAX_DETAIL_CORRELATION: f64 = 0.5;
+/// Pixels a block needs for its correlation to mean something, smaller ones at the edges of an
+/// export are only checked for copied pixels.
+const MIN_CORRELATED_PIXELS: usize = 100;
+
+/// How the content under a redaction rectangle is hidden.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Redaction {
+    Blur {
+        radius: usize,
+        kernel: BlurKernel,
+    },
+    Pixelate {
+        block_size: usize,
+    },
+    Solid,
+    /// Coarse pixelation plus noise which is guaranteed to change every pixel.
+    Secure {
+        seed: u64,
+    },
+}
+
+/// Why a redaction may leave original content in an exported image.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RedactionIssue {
+    /// Blurred or pixelated content can be partially reconstructed.
+    Recoverable,
+    /// Number of pixels which are identical to the original screenshot.
+    Leaking(usize),
+}
+
+impl Redaction {
+    pub fn secure() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+
+        Redaction::Secure { seed }
+    }
+
+    /// Cycles blur -> pixelate -> solid -> secure -> blur. New blur and pixelation use the given
+    /// radius and block size.
+    pub fn next(self, radius: usize, block_size: usize) -> Self {
+        match self {
+            Redaction::Blur { .. } => Redaction::Pixelate { block_size },
+            Redaction::Pixelate { .. } => Redaction::Solid,
+            Redaction::Solid => Redaction::secure(),
+            Redaction::Secure { .. } => Redaction::Blur {
+                radius,
+                kernel: BlurKernel::Gaussian,
+            },
+        }
+    }
+
+    /// Increases the blur radius or the pixelation block size.
+    pub fn stronger(self) -> Self {
+        match self {
+            Redaction::Blur { radius, kernel } => Redaction::Blur {
+                radius: (radius + 1).min(MAX_RADIUS),
+                kernel,
+            },
+            Redaction::Pixelate { block_size } => Redaction::Pixelate {
+                block_size: (block_size + 1).min(MAX_BLOCK_SIZE),
+            },
+            Redaction::Solid | Redaction::Secure { .. } => self,
+        }
+    }
+
+    /// Decreases the blur radius or the pixelation block size.
+    pub fn weaker(self) -> Self {
+        match self {
+            Redaction::Blur { radius, kernel } => Redaction::Blur {
+                radius: radius.saturating_sub(1).max(1),
+                kernel,
+            },
+            Redaction::Pixelate { block_size } => Redaction::Pixelate {
+                block_size: block_size.saturating_sub(1).max(MIN_BLOCK_SIZE),
+            },
+            Redaction::Solid | Redaction::Secure { .. } => self,
+        }
+    }
+
+    pub fn next_kernel(self) -> Self {
+        match self {
+            Redaction::Blur { radius, kernel } => Redaction::Blur {
+                radius,
+                kernel: kernel.next(),
+            },
+            _ => self,
+        }
+    }
+}
+
+/// Hides the rectangle's content. `original` is the screenshot the secure redaction has to
+/// differ from.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_redaction(
+    canvas: &mut [u8],
+    original: &[u8],
+    cache: &mut BlurCache,
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    redaction: Redaction,
+) {
+    match redaction {
+        Redaction::Blur { radius, kernel } => {
+            draw_rect_blurred(canvas, x0, y0, x1, y1, width, radius, kernel, cache)
+        }
+        Redaction::Pixelate { block_size } => {
+            draw_rect_pixelated(canvas, x0, y0, x1, y1, width, block_size)
+        }
+        Redaction::Solid => draw_rect_filled(canvas, x0, y0, x1, y1, width, SOLID_COLOR),
+        Redaction::Secure { seed } => {
+            draw_rect_secure(canvas, original, x0, y0, x1, y1, width, seed)
+        }
+    }
+}
+
+/// Checks that no original pixel inside of a redaction survived in an exported `image` whose
+/// top left corner was at `origin` of the screenshot, and that no original detail can be
+/// recovered: within every block of the secure pixelation the exported pixels must not
+/// correlate with the original ones, even inversely. Every pixel of a block which does counts as
+/// leaking.
+pub fn verify_redaction(
+    original: &[u8],
+    width: usize,
+    image: &Image,
+    origin: Pos2,
+    (p0, p1): (Pos2, Pos2),
+    redaction: Redaction,
+) -> Result<(), RedactionIssue> {
+    if let Redaction::Blur { .. } | Redaction::Pixelate { .. } = redaction {
+        return Err(RedactionIssue::Recoverable);
+    }
+
+    let (x0, x1) = (p0.0.min(p1.0).max(origin.0), p0.0.max(p1.0));
+    let (y0, y1) = (p0.1.min(p1.1).max(origin.1), p0.1.max(p1.1));
+    let x1 = x1.min(origin.0 + image.width);
+    let y1 = y1.min(origin.1 + image.height);
+
+    // blocks are laid out from the redaction's corner, as the secure pixelation does
+    let grid = (p0.0.min(p1.0), p0.1.min(p1.1));
+    let block_start = |v: usize, start: usize| v - (v - start) % SECURE_BLOCK_SIZE;
+
+    let mut leaking = 0;
+    for by in (block_start(y0, grid.1)..y1).step_by(SECURE_BLOCK_SIZE) {
+        for bx in (block_start(x0, grid.0)..x1).step_by(SECURE_BLOCK_SIZE) {
+            let mut copied = 0;
+            let mut pairs = vec![];
+            for y in by.max(y0)..(by + SECURE_BLOCK_SIZE).min(y1) {
+                for x in bx.max(x0)..(bx + SECURE_BLOCK_SIZE).min(x1) {
+                    let i = (y - origin.1) * (image.width * 4) + (x - origin.0) * 4;
+                    let exported = &image.bytes[i..i + 3];
+                    let j = y * (width * 4) + x * 4;
+                    let Some(before) = original.get(j..j + 3) else {
+                        continue;
+                    };
+
+                    let solid = redaction == Redaction::Solid
+                        && exported == [SOLID_COLOR.0, SOLID_COLOR.1, SOLID_COLOR.2];
+                    if exported == before && !solid {
+                        copied += 1;
+                    }
+                    pairs.push((luma(before), luma(exported)));
+                }
+            }
+
+            if pairs.len() >= MIN_CORRELATED_PIXELS
+                && correlation(&pairs).abs() > MAX_DETAIL_CORRELATION
+            {
+                leaking += pairs.len();
+            } else {
+                leaking += copied;
+            }
+        }
+    }
+
+    if leaking == 0 {
+        Ok(())
+    } else {
+        Err(RedactionIssue::Leaking(leaking))
+    }
+}
+
+fn luma(rgb: &[u8]) -> f64 {
+    0.299 * rgb[0] as f64 + 0.587 * rgb[1] as f64 + 0.114 * rgb[2] as f64
+}
+
+/// Pearson correlation of the pairs, 0 when either side doesn't vary.
+fn correlation(pairs: &[(f64, f64)]) -> f64 {
+    let n = pairs.len() as f64;
+    let (mean_a, mean_b) = pairs
+        .iter()
+        .fold((0., 0.), |(a, b), (x, y)| (a + x / n, b + y / n));
+    let (mut covariance, mut variance_a, mut variance_b) = (0., 0., 0.);
+    for (a, b) in pairs {
+        covariance += (a - mean_a) * (b - mean_b);
+        variance_a += (a - mean_a).powi(2);
+        variance_b += (b - mean_b).powi(2);
+    }
+
+    if variance_a < f64::EPSILON || variance_b < f64::EPSILON {
+        0.
+    } else {
+        covariance / (variance_a * variance_b).sqrt()
+    }
+}
+
+/// Replaces every `block_size` square of the rectangle with its average color.
+pub fn draw_rect_pixelated(
+    canvas: &mut [u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    block_size: usize,
+) {
+    let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
+    let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
+    let height = canvas.len() / (width * 4).max(1);
+    let (x1, y1) = (x1.min(width), y1.min(height));
+    let block_size = block_size.max(1);
+
+    for by in (y0..y1).step_by(block_size) {
+        for bx in (x0..x1).step_by(block_size) {
+            let (rows, columns) = (by..(by + block_size).min(y1), bx..(bx + block_size).min(x1));
+
+            let mut sum = [0usize; 3];
+            for hh in rows.clone() {
+                for ww in columns.clone() {
+                    let i = hh * (width * 4) + (ww * 4);
+                    sum[0] += canvas[i] as usize;
+                    sum[1] += canvas[i + 1] as usize;
+                    sum[2] += canvas[i + 2] as usize;
+                }
+            }
+
+            let count = rows.len() * columns.len();
+            let [r, g, b] = sum.map(|channel| (channel / count) as u8);
+            for hh in rows.clone() {
+                for ww in columns.clone() {
+                    let i = hh * (width * 4) + (ww * 4);
+                    blend_pixel(canvas, i, (r, g, b, 255), 1., BlendMode::SourceOver);
+                }
+            }
+        }
+    }
+}
+
+/// Pixelates the rectangle coarsely and adds noise, changing every pixel compared to `original`.
+#[allow(clippy::too_many_arguments)]
+fn draw_rect_secure(
+    canvas: &mut [u8],
+    original: &[u8],
+    x0: usize,
+    y0: usize,
+    x1: usize,
+    y1: usize,
+    width: usize,
+    seed: u64,
+) {
+    draw_rect_pixelated(canvas, x0, y0, x1, y1, width, SECURE_BLOCK_SIZE);
+
+    let (x0, x1) = if x0 > x1 { (x1, x0) } else { (x0, x1) };
+    let (y0, y1) = if y0 > y1 { (y1, y0) } else { (y0, y1) };
+    let height = canvas.len() / (width * 4).max(1);
+
+    for hh in y0..y1.min(height) {
+        for ww in x0..x1.min(width) {
+            let i = hh * (width * 4) + (ww * 4);
+            let mut noise = splitmix64(seed ^ ((hh as u64) << 32 | ww as u64));
+            let mut rgb = [canvas[i], canvas[i + 1], canvas[i + 2]];
+            for channel in &mut rgb {
+                let shift = (noise % (2 * SECURE_NOISE as u64 + 1)) as i32 - SECURE_NOISE;
+                *channel = (*channel as i32 + shift).clamp(0, 255) as u8;
+                noise >>= 16;
+            }
+
+            if original.get(i..i + 3) == Some(&rgb[..]) {
+                rgb[0] ^= 0x80;
+            }
+            let [r, g, b] = rgb;
+            blend_pixel(canvas, i, (r, g, b, 255), 1., BlendMode::SourceOver);
+        }
+    }
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    #[test]
+    fn pixelate_averages_blocks() {
+        let mut canvas: Vec<u8> = (0..WIDTH * HEIGHT)
+            .flat_map(|i| [(i % WIDTH) as u8, (i / WIDTH) as u8, 0, 255])
+            .collect();
+        draw_rect_pixelated(&mut canvas, 10, 10, 30, 25, WIDTH, 10);
+
+        let rgba = |x: usize, y: usize| &canvas[y * WIDTH * 4 + x * 4..y * WIDTH * 4 + x * 4 + 4];
+        // averages of x in 10..20 and 20..30, and of y in 10..20 and 20..25
+        assert_eq!(rgba(10, 10), [14, 14, 0, 255]);
+        assert_eq!(rgba(19, 19), [14, 14, 0, 255]);
+        assert_eq!(rgba(29, 24), [24, 22, 0, 255]);
+        assert_eq!(rgba(30, 24), [30, 24, 0, 255]);
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{circle::draw_circle_filled, text::draw_text_centered, Pos2};
+
+pub const RADIUS: usize = 16;
+const FONT_SIZE: f32 = 20.;
+
+/// Draws a filled circle with the step `number` centered inside of it.
+pub fn draw_step(
+    canvas: &mut [u8],
+    (x, y): Pos2,
+    number: usize,
+    width: usize,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+) {
+    draw_circle_filled(canvas, x, y, RADIUS, width, antialiasing, color);
+
+    draw_text_centered(
+        canvas,
+        width,
+        FONT_SIZE,
+        &number.to_string(),
+        (x, y),
+        contrasting(color),
+    );
+}
+
+/// Black or white, whichever is better readable on top of `color`.
+fn contrasting((r, g, b, a): (u8, u8, u8, u8)) -> (u8, u8, u8, u8) {
+    let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+
+    if luma > 150. {
+        (0, 0, 0, a)
+    } else {
+        (255, 255, 255, a)
+    }
+}
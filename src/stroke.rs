@@ -0,0 +1,367 @@
+use crate::blend::{blend_pixel, BlendMode};
+
+const MITER_LIMIT: f64 = 4.;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cap {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Join {
+    Miter,
+    Round,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stroke {
+    pub width: f64,
+    pub cap: Cap,
+    pub join: Join,
+}
+
+enum Shape {
+    Polygon(Vec<(f64, f64)>),
+    Circle((f64, f64), f64),
+}
+
+/// Strokes a polyline with the given width, caps and joins. Every covered pixel is blended
+/// once with `mode`, so overlapping segments and joins don't show seams.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_polyline(
+    canvas: &mut [u8],
+    width: usize,
+    points: &[(f64, f64)],
+    closed: bool,
+    stroke: Stroke,
+    antialiasing: bool,
+    color: (u8, u8, u8, u8),
+    mode: BlendMode,
+) {
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+
+    let shapes = outline(&points, closed, stroke);
+    if shapes.is_empty() || width == 0 {
+        return;
+    }
+
+    let height = canvas.len() / (width * 4);
+    let reach = stroke.width / 2. * MITER_LIMIT + 2.;
+    let (x_min, x_max) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), p| {
+        (min.min(p.0), max.max(p.0))
+    });
+    let (y_min, y_max) = points.iter().fold((f64::MAX, f64::MIN), |(min, max), p| {
+        (min.min(p.1), max.max(p.1))
+    });
+
+    let clip = |v: f64, max: usize| (v.max(0.) as usize).min(max);
+    let x_min = clip((x_min - reach).floor(), width);
+    let y_min = clip((y_min - reach).floor(), height);
+    let x_max = clip((x_max + reach).ceil(), width);
+    let y_max = clip((y_max + reach).ceil(), height);
+    if x_min >= x_max || y_min >= y_max {
+        return;
+    }
+
+    // coverage is accumulated as the maximum over all shapes, so pixels shared by segments and
+    // joins are blended once
+    let area_width = x_max - x_min;
+    let mut coverage = vec![0u8; area_width * (y_max - y_min)];
+    for shape in &shapes {
+        let (sx0, sy0, sx1, sy1) = shape_bounds(shape);
+        for y in clip(sy0.floor(), y_max).max(y_min)..clip(sy1.ceil() + 1., y_max) {
+            for x in clip(sx0.floor(), x_max).max(x_min)..clip(sx1.ceil() + 1., x_max) {
+                let c = shape_coverage(shape, (x as f64 + 0.5, y as f64 + 0.5));
+                let c = if antialiasing {
+                    (c * 255.).round() as u8
+                } else if c >= 0.5 {
+                    255
+                } else {
+                    0
+                };
+
+                let cell = &mut coverage[(y - y_min) * area_width + (x - x_min)];
+                *cell = (*cell).max(c);
+            }
+        }
+    }
+
+    for y in y_min..y_max {
+        for x in x_min..x_max {
+            let c = coverage[(y - y_min) * area_width + (x - x_min)];
+            if c != 0 {
+                blend_pixel(
+                    canvas,
+                    y * (width * 4) + (x * 4),
+                    color,
+                    c as f64 / 255.,
+                    mode,
+                );
+            }
+        }
+    }
+}
+
+fn shape_bounds(shape: &Shape) -> (f64, f64, f64, f64) {
+    match shape {
+        Shape::Circle((x, y), r) => (x - r - 1., y - r - 1., x + r + 1., y + r + 1.),
+        Shape::Polygon(vertices) => vertices.iter().fold(
+            (f64::MAX, f64::MAX, f64::MIN, f64::MIN),
+            |(x0, y0, x1, y1), (x, y)| {
+                (
+                    x0.min(x - 1.),
+                    y0.min(y - 1.),
+                    x1.max(x + 1.),
+                    y1.max(y + 1.),
+                )
+            },
+        ),
+    }
+}
+
+fn outline(points: &[(f64, f64)], closed: bool, stroke: Stroke) -> Vec<Shape> {
+    let half = stroke.width.max(1.) / 2.;
+    let mut shapes = vec![];
+
+    if points.len() == 1 {
+        if stroke.cap == Cap::Round {
+            shapes.push(Shape::Circle(points[0], half));
+        }
+        return shapes;
+    }
+
+    let segments = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+    for i in 0..segments {
+        let (mut a, mut b) = (points[i], points[(i + 1) % points.len()]);
+        let (dx, dy) = direction(a, b);
+
+        if !closed && stroke.cap == Cap::Square {
+            if i == 0 {
+                a = (a.0 - dx * half, a.1 - dy * half);
+            }
+            if i == segments - 1 {
+                b = (b.0 + dx * half, b.1 + dy * half);
+            }
+        }
+
+        let (nx, ny) = (-dy * half, dx * half);
+        shapes.push(Shape::Polygon(vec![
+            (a.0 + nx, a.1 + ny),
+            (b.0 + nx, b.1 + ny),
+            (b.0 - nx, b.1 - ny),
+            (a.0 - nx, a.1 - ny),
+        ]));
+    }
+
+    if !closed && stroke.cap == Cap::Round {
+        shapes.push(Shape::Circle(points[0], half));
+        shapes.push(Shape::Circle(points[points.len() - 1], half));
+    }
+
+    let joints = if closed {
+        0..points.len()
+    } else {
+        1..points.len() - 1
+    };
+    for i in joints {
+        let vertex = points[i];
+        let previous = points[(i + points.len() - 1) % points.len()];
+        let next = points[(i + 1) % points.len()];
+
+        match stroke.join {
+            Join::Round => shapes.push(Shape::Circle(vertex, half)),
+            Join::Miter => shapes.push(miter(previous, vertex, next, half)),
+        }
+    }
+
+    shapes
+}
+
+/// Fills the wedge between two segments meeting at `vertex` on the outer side of the turn,
+/// falling back to a bevel when the tip would be longer than [`MITER_LIMIT`].
+fn miter(previous: (f64, f64), vertex: (f64, f64), next: (f64, f64), half: f64) -> Shape {
+    let (d0x, d0y) = direction(previous, vertex);
+    let (d1x, d1y) = direction(vertex, next);
+
+    let turn = d0x * d1y - d0y * d1x;
+    let side = if turn > 0. { -1. } else { 1. };
+    let n0 = (-d0y * half * side, d0x * half * side);
+    let n1 = (-d1y * half * side, d1x * half * side);
+
+    let a = (vertex.0 + n0.0, vertex.1 + n0.1);
+    let b = (vertex.0 + n1.0, vertex.1 + n1.1);
+
+    let (mx, my) = (n0.0 + n1.0, n0.1 + n1.1);
+    let m_length = (mx * mx + my * my).sqrt();
+    let cos_half_angle = m_length / (2. * half);
+
+    if cos_half_angle > 1. / MITER_LIMIT {
+        let length = half / cos_half_angle;
+        let tip = (
+            vertex.0 + mx / m_length * length,
+            vertex.1 + my / m_length * length,
+        );
+        Shape::Polygon(vec![vertex, a, tip, b])
+    } else {
+        Shape::Polygon(vec![vertex, a, b])
+    }
+}
+
+fn direction(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0. {
+        (1., 0.)
+    } else {
+        (dx / length, dy / length)
+    }
+}
+
+fn shape_coverage(shape: &Shape, p: (f64, f64)) -> f64 {
+    match shape {
+        Shape::Circle(center, r) => {
+            let distance = ((p.0 - center.0).powi(2) + (p.1 - center.1).powi(2)).sqrt();
+            (r - distance + 0.5).clamp(0., 1.)
+        }
+        Shape::Polygon(vertices) => {
+            let area: f64 = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+                .sum();
+            if area.abs() < f64::EPSILON {
+                return 0.;
+            }
+            let orientation = area.signum();
+
+            let distance = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .map(|(a, b)| {
+                    let (ex, ey) = (b.0 - a.0, b.1 - a.1);
+                    let length = (ex * ex + ey * ey).sqrt();
+                    if length == 0. {
+                        f64::MAX
+                    } else {
+                        orientation * (ex * (p.1 - a.1) - ey * (p.0 - a.0)) / length
+                    }
+                })
+                .fold(f64::MAX, f64::min);
+
+            (distance + 0.5).clamp(0., 1.)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: usize = 200;
+    const HEIGHT: usize = 100;
+
+    /// Coverage of every pixel of a white stroke drawn on a transparent canvas.
+    fn stroke_coverage(points: &[(f64, f64)], stroke: Stroke) -> Vec<f64> {
+        let mut canvas = vec![0; WIDTH * HEIGHT * 4];
+        draw_polyline(
+            &mut canvas,
+            WIDTH,
+            points,
+            false,
+            stroke,
+            true,
+            (255, 255, 255, 255),
+            BlendMode::SourceOver,
+        );
+        canvas.chunks(4).map(|p| p[3] as f64 / 255.).collect()
+    }
+
+    #[test]
+    fn strokes_are_as_wide_at_any_angle() {
+        let stroke = Stroke {
+            width: 8.,
+            cap: Cap::Butt,
+            join: Join::Miter,
+        };
+        let diagonal = 80. / 2f64.sqrt();
+        for points in [
+            [(40., 50.), (120., 50.)],
+            [(40., 10.), (40. + diagonal, 10. + diagonal)],
+            [(100., 10.), (100., 90.)],
+        ] {
+            // 80 px long, so the area is 80 times the width
+            let area: f64 = stroke_coverage(&points, stroke).iter().sum();
+            assert!(
+                (area - 640.).abs() < 640. * 0.02,
+                "{points:?} covers {area}"
+            );
+        }
+    }
+
+    #[test]
+    fn stroke_caps_extend_by_half_the_width() {
+        let coverage = |cap| {
+            let stroke = Stroke {
+                width: 10.,
+                cap,
+                join: Join::Miter,
+            };
+            let coverage = stroke_coverage(&[(60., 50.), (100., 50.)], stroke);
+            let area: f64 = coverage.iter().sum();
+            (move |x: usize, y: usize| coverage[y * WIDTH + x], area)
+        };
+
+        let (butt, area) = coverage(Cap::Butt);
+        assert_eq!((butt(58, 50), butt(60, 50), butt(100, 50)), (0., 1., 0.));
+        assert!((area - 400.).abs() < 8.);
+
+        let (square, area) = coverage(Cap::Square);
+        assert_eq!(
+            (square(55, 50), square(104, 54), square(105, 50)),
+            (1., 1., 0.)
+        );
+        assert!((area - 500.).abs() < 10.);
+
+        let (round, area) = coverage(Cap::Round);
+        // pixel centers are half a pixel off the axis, so not quite covered
+        assert!(round(55, 50) > 0.9 && round(104, 50) > 0.9);
+        assert_eq!(round(104, 54), 0.);
+        assert!((area - (400. + std::f64::consts::PI * 25.)).abs() < 10.);
+    }
+
+    #[test]
+    fn stroke_joins_leave_no_gaps() {
+        let corner = [(40., 80.), (100., 80.), (100., 20.)];
+        for join in [Join::Miter, Join::Round] {
+            let stroke = Stroke {
+                width: 10.,
+                cap: Cap::Butt,
+                join,
+            };
+            let coverage = stroke_coverage(&corner, stroke);
+            // outside of both segments, only the join covers it
+            assert_eq!(coverage[82 * WIDTH + 102], 1., "{join:?}");
+            // every pixel of the band around the corner is covered
+            for (x, y) in (95..100).flat_map(|x| (75..85).map(move |y| (x, y))) {
+                assert_eq!(coverage[y * WIDTH + x], 1., "{join:?} at {x},{y}");
+            }
+        }
+
+        let miter = Stroke {
+            width: 10.,
+            cap: Cap::Butt,
+            join: Join::Miter,
+        };
+        assert_eq!(stroke_coverage(&corner, miter)[84 * WIDTH + 104], 1.);
+    }
+}
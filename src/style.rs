@@ -0,0 +1,75 @@
+use crate::BorderColor;
+
+pub const DEFAULT_STROKE_WIDTH: usize = 3;
+pub const MAX_STROKE_WIDTH: usize = 32;
+const OPACITY_STEP: u8 = 51;
+
+/// Colors switched between unless the config file sets a palette.
+pub const DEFAULT_PALETTE: [BorderColor; 7] = [
+    BorderColor::rgb(255, 0, 0),
+    BorderColor::rgb(255, 0, 255),
+    BorderColor::rgb(255, 255, 0),
+    BorderColor::rgb(0, 255, 0),
+    BorderColor::rgb(0, 0, 255),
+    BorderColor::rgb(0, 0, 0),
+    BorderColor::rgb(255, 255, 255),
+];
+
+/// Appearance of a drawn item. It's copied from the active style when the item is created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Style {
+    pub color: BorderColor,
+    pub stroke_width: usize,
+    pub opacity: u8,
+    pub fill: bool,
+}
+
+impl Style {
+    pub fn new(color: BorderColor) -> Self {
+        Self {
+            color,
+            stroke_width: DEFAULT_STROKE_WIDTH,
+            opacity: 255,
+            fill: false,
+        }
+    }
+
+    pub fn filled(self, fill: bool) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Color with opacity applied to its alpha channel.
+    pub fn rgba(&self) -> (u8, u8, u8, u8) {
+        let BorderColor { r, g, b, a } = self.color;
+
+        (r, g, b, (a as usize * self.opacity as usize / 255) as u8)
+    }
+
+    /// Switches to the color following the current one in `palette`, or to its first color.
+    pub fn next_color(&mut self, palette: &[BorderColor]) {
+        let next = palette
+            .iter()
+            .position(|c| *c == self.color)
+            .map_or(0, |i| (i + 1) % palette.len());
+
+        if let Some(color) = palette.get(next) {
+            self.color = *color;
+        }
+    }
+
+    pub fn thicker(&mut self) {
+        self.stroke_width = (self.stroke_width + 1).min(MAX_STROKE_WIDTH);
+    }
+
+    pub fn thinner(&mut self) {
+        self.stroke_width = self.stroke_width.saturating_sub(1).max(1);
+    }
+
+    pub fn more_opaque(&mut self) {
+        self.opacity = self.opacity.saturating_add(OPACITY_STEP);
+    }
+
+    pub fn more_transparent(&mut self) {
+        self.opacity = self.opacity.saturating_sub(OPACITY_STEP).max(OPACITY_STEP);
+    }
+}
@@ -1,14 +1,21 @@
+use std::{cell::RefCell, rc::Rc};
+
 use fontdue::{
     layout::{CoordinateSystem, Layout, LayoutSettings, TextStyle},
-    Font, Metrics,
+    Font,
 };
 
-use crate::{keycode_to_text::Cursor, rectangle::draw_rect_filled, Pos2};
+use crate::{antialias::plot, keycode_to_text::Cursor, rectangle::draw_rect_filled, Pos2};
+
+/// Size of drawn texts unless the config file sets one.
+pub const DEFAULT_FONT_SIZE: f32 = 24.;
+/// Height of the text cursor and advance of a space, relative to the font size.
+const CURSOR_HEIGHT: f32 = 1.25;
+const SPACE_WIDTH: f32 = 0.625;
 
+/// Blends the laid out glyphs into the canvas, using their grayscale coverage as alpha.
 pub fn draw_text(
     canvas: &mut [u8],
-    x0: usize,
-    y0: usize,
     canvas_width: usize,
     color: (u8, u8, u8, u8),
     layout: &Layout,
@@ -21,59 +28,79 @@ pub fn draw_text(
             continue;
         }
 
-        let (metrics, rgb_bitmap) = get_rasterized_glyph(
-            &fonts[0],
-            gl.key.glyph_index,
-            24,
-            (
-                color.0 as usize,
-                color.1 as usize,
-                color.2 as usize,
-                color.3 as usize,
-            ),
-            (0, 0, 0, 255),
-        );
-
-        let start = (x0, y0);
-        if gl.x < 1920. {
-            for hh in 0..metrics.height {
-                if canvas
-                    .get(
-                        // (start.1 + hh + gl.y as usize) * canvas_width
-                        ((hh + gl.y as usize) - start.1) * canvas_width
-                                // + start.0
-                                + metrics.width
-                                + gl.x as usize
-                            - 1
-                            - start.0,
-                    )
-                    .is_some()
-                {
-                    canvas[(hh + gl.y as usize) * (canvas_width * 4) + (gl.x as usize * 4)
-                        ..(hh + gl.y as usize) * (canvas_width * 4)
-                            + (metrics.width * 4)
-                            + (gl.x as usize * 4)]
-                        .copy_from_slice(
-                            &rgb_bitmap[hh * (metrics.width * 4)
-                                ..hh * (metrics.width * 4) + (metrics.width * 4)],
-                        );
-                }
+        let (metrics, bitmap) = fonts[0].rasterize_indexed(gl.key.glyph_index, gl.key.px);
+
+        for hh in 0..metrics.height {
+            for ww in 0..metrics.width {
+                plot(
+                    canvas,
+                    canvas_width,
+                    gl.x as isize + ww as isize,
+                    gl.y as isize + hh as isize,
+                    color,
+                    bitmap[hh * metrics.width + ww] as f64 / 255.,
+                );
             }
         }
     }
 }
 
-pub fn init_layout(size_px: f32, content: &str, x: f32, y: f32) -> (Layout, Vec<Font>) {
-    let settings = fontdue::FontSettings {
-        scale: size_px,
-        ..fontdue::FontSettings::default()
+thread_local! {
+    /// Parsed fonts by their size. Parsing is much slower than laying out, and overlay texts are
+    /// laid out on every redraw.
+    static FONTS: RefCell<Vec<(f32, Rc<[Font]>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Draws a single line of text whose glyphs are centered on `center`.
+pub fn draw_text_centered(
+    canvas: &mut [u8],
+    canvas_width: usize,
+    size_px: f32,
+    content: &str,
+    center: Pos2,
+    color: (u8, u8, u8, u8),
+) {
+    let (layout, _) = init_layout(size_px, content, 0., 0.);
+    let Some((x0, y0, x1, y1)) = layout.glyphs().iter().fold(None, |bounds, gl| {
+        let (x0, y0, x1, y1) = bounds.unwrap_or((f32::MAX, f32::MAX, f32::MIN, f32::MIN));
+        Some((
+            x0.min(gl.x),
+            y0.min(gl.y),
+            x1.max(gl.x + gl.width as f32),
+            y1.max(gl.y + gl.height as f32),
+        ))
+    }) else {
+        return;
     };
-    let font = fontdue::Font::from_bytes(
-        include_bytes!("../JetBrainsMono-Regular.ttf") as &[u8],
-        settings,
-    )
-    .unwrap();
-    let fonts = vec![font.clone()];
+
+    let (layout, fonts) = init_layout(
+        size_px,
+        content,
+        center.0 as f32 - (x0 + x1) / 2.,
+        center.1 as f32 - (y0 + y1) / 2.,
+    );
+    draw_text(canvas, canvas_width, color, &layout, &fonts);
+}
+
+pub fn init_layout(size_px: f32, content: &str, x: f32, y: f32) -> (Layout, Rc<[Font]>) {
+    let fonts = FONTS.with(|fonts| {
+        let mut fonts = fonts.borrow_mut();
+        if let Some((_, font)) = fonts.iter().find(|(size, _)| *size == size_px) {
+            return font.clone();
+        }
+
+        let settings = fontdue::FontSettings {
+            scale: size_px,
+            ..fontdue::FontSettings::default()
+        };
+        let font: Rc<[Font]> = Rc::new([fontdue::Font::from_bytes(
+            include_bytes!("../JetBrainsMono-Regular.ttf") as &[u8],
+            settings,
+        )
+        .unwrap()]);
+        fonts.push((size_px, font.clone()));
+        font
+    });
 
     let mut layout = Layout::new(CoordinateSystem::PositiveYDown);
     layout.reset(&LayoutSettings {
@@ -81,53 +108,38 @@ pub fn init_layout(size_px: f32, content: &str, x: f32, y: f32) -> (Layout, Vec<
         y,
         ..LayoutSettings::default()
     });
-    layout.append(&[font.clone()], &TextStyle::new(content, size_px, 0));
+    layout.append(&fonts, &TextStyle::new(content, size_px, 0));
 
     (layout, fonts)
 }
 
-pub fn get_rasterized_glyph(
-    font: &Font,
-    glyph_index: u16,
-    size_px: usize,
-    color: (usize, usize, usize, usize),
-    bg_color: (usize, usize, usize, usize),
-) -> (Metrics, Vec<u8>) {
-    let (metrics, bitmap) = font.rasterize_indexed_subpixel(glyph_index, size_px as f32);
-
-    let rgb_bitmap: Vec<u8> = bitmap
-        .chunks(3)
-        .flat_map(|chunk| {
-            let bg_blue = chunk[2] as u32;
-            let bg_green = chunk[1] as u32;
-            let bg_red = chunk[0] as u32;
-
-            convert_rgba((bg_red, bg_green, bg_blue, 255), bg_color, color)
-        })
-        .collect();
-
-    (metrics, rgb_bitmap)
+/// Horizontal extent of a text laid out at x = 0.
+pub fn text_width(layout: &Layout) -> usize {
+    layout
+        .glyphs()
+        .iter()
+        .map(|gl| gl.x as usize + gl.width)
+        .max()
+        .unwrap_or(0)
 }
 
-fn convert_rgba(
-    current: (u32, u32, u32, u32),
-    bg: (usize, usize, usize, usize),
-    fg: (usize, usize, usize, usize),
-) -> Vec<u8> {
-    let new_r = ((current.0 as f64 / 255.0) * fg.0 as f64
-        + (1.0 - current.0 as f64 / 255.0) * bg.0 as f64) as u8;
-
-    let new_g = ((current.1 as f64 / 255.0) * fg.1 as f64
-        + (1.0 - current.1 as f64 / 255.0) * bg.1 as f64) as u8;
+/// Returns the padded area occupied by a laid out text, if it has any glyphs.
+pub fn text_bounds(layout: &Layout) -> Option<(Pos2, Pos2)> {
+    let (first, last) = (layout.glyphs().first()?, layout.glyphs().last()?);
 
-    let new_b = ((current.2 as f64 / 255.0) * fg.2 as f64
-        + (1.0 - current.2 as f64 / 255.0) * bg.2 as f64) as u8;
-
-    let alpha = 255;
-
-    vec![new_r, new_g, new_b, alpha]
+    Some((
+        (
+            (first.x as usize).saturating_sub(5),
+            (first.y as usize).saturating_sub(5),
+        ),
+        (
+            (last.x as usize + last.width) + 5,
+            (last.y as usize + last.height) + 5,
+        ),
+    ))
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn draw_cursor(
     canvas: &mut [u8],
     canvas_width: usize,
@@ -135,6 +147,7 @@ pub fn draw_cursor(
     layout: &Layout,
     content: &str,
     start: Pos2,
+    font_size: f32,
     cursor_color: (u8, u8, u8, u8),
 ) {
     let mut skipped_lines_chars = 0;
@@ -153,7 +166,8 @@ pub fn draw_cursor(
     let cursor_position = cursor
         .global_chars_offset_from_beginning
         .saturating_sub(skip_chars);
-    let cursor_offset = update_cusror_position_text(layout, cursor_position, (start.0, start.1));
+    let cursor_offset =
+        update_cusror_position_text(layout, cursor_position, (start.0, start.1), font_size);
     cursor.px_coordinates = cursor_offset;
 
     draw_rect_filled(
@@ -161,13 +175,19 @@ pub fn draw_cursor(
         cursor.px_coordinates.0,
         cursor.px_coordinates.1,
         cursor.px_coordinates.0 + 2,
-        cursor.px_coordinates.1 + 30,
+        cursor.px_coordinates.1 + (font_size * CURSOR_HEIGHT) as usize,
         canvas_width,
         cursor_color,
     );
 }
 
-pub fn update_cusror_position_text(layout: &Layout, cursor_position: usize, start: Pos2) -> Pos2 {
+pub fn update_cusror_position_text(
+    layout: &Layout,
+    cursor_position: usize,
+    start: Pos2,
+    font_size: f32,
+) -> Pos2 {
+    let space_width = (font_size * SPACE_WIDTH) as usize;
     let gl = layout
         .glyphs()
         .get(cursor_position.saturating_sub(1))
@@ -175,10 +195,15 @@ pub fn update_cusror_position_text(layout: &Layout, cursor_position: usize, star
     let cursor_offset = if let (Some(gl), true) = (gl, cursor_position != 0) {
         (
             std::cmp::max(
-                gl.x as usize + (if gl.parent != ' ' { gl.width } else { 15 }),
+                gl.x as usize
+                    + (if gl.parent != ' ' {
+                        gl.width
+                    } else {
+                        space_width
+                    }),
                 start.0,
             ),
-            (gl.y as usize) - layout.glyphs().last().unwrap().y as usize + start.1,
+            (gl.y as usize + start.1).saturating_sub(layout.glyphs().last().unwrap().y as usize),
         )
     } else {
         (start.0, start.1)
@@ -0,0 +1,230 @@
+use crate::{
+    antialias::draw_triangle_filled_aa,
+    circle::draw_ellipse_bordered,
+    line::draw_line_thick,
+    rect::Rect,
+    rectangle::{draw_rect_bordered, draw_rect_filled},
+    text::draw_text_centered,
+    DrawMode, Pos2,
+};
+
+pub const BUTTON_SIZE: usize = 24;
+const GAP: usize = 2;
+const PADDING: usize = 4;
+/// Distance between the selection and the toolbar.
+const MARGIN: usize = 6;
+const FONT_SIZE: f32 = 16.;
+const BACKGROUND: (u8, u8, u8, u8) = (40, 40, 40, 230);
+const ICON_COLOR: (u8, u8, u8, u8) = (255, 255, 255, 255);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Button {
+    Tool(DrawMode),
+    Color,
+    Thinner,
+    Thicker,
+    Undo,
+    Copy,
+    Save,
+}
+
+pub const BUTTONS: [Button; 20] = [
+    Button::Tool(DrawMode::Arrow),
+    Button::Tool(DrawMode::ArrowFilled),
+    Button::Tool(DrawMode::Line),
+    Button::Tool(DrawMode::RectBorder),
+    Button::Tool(DrawMode::RectFilled),
+    Button::Tool(DrawMode::Ellipse),
+    Button::Tool(DrawMode::Step),
+    Button::Tool(DrawMode::Path),
+    Button::Tool(DrawMode::Highlight),
+    Button::Tool(DrawMode::RectBlurred),
+    Button::Tool(DrawMode::RectPixelated),
+    Button::Tool(DrawMode::RectSecure),
+    Button::Tool(DrawMode::Text),
+    Button::Tool(DrawMode::Picker),
+    Button::Color,
+    Button::Thinner,
+    Button::Thicker,
+    Button::Undo,
+    Button::Copy,
+    Button::Save,
+];
+
+/// Buttons laid out in as many rows as the canvas width requires, next to the selection.
+pub struct Toolbar {
+    origin: Pos2,
+    columns: usize,
+    canvas: Pos2,
+}
+
+impl Toolbar {
+    /// Places the toolbar right-aligned below the selection, above it when there is no room
+    /// below, or inside of its bottom edge when the selection covers the whole height.
+    pub fn new(selection: Rect, (width, height): Pos2) -> Self {
+        let fitting = (width.saturating_sub(2 * PADDING) + GAP) / (BUTTON_SIZE + GAP);
+        let columns = fitting.clamp(1, BUTTONS.len());
+        let mut toolbar = Self {
+            origin: (0, 0),
+            columns,
+            canvas: (width, height),
+        };
+
+        let (w, h) = toolbar.size();
+        let y = if selection.p1().1 + MARGIN + h <= height {
+            selection.p1().1 + MARGIN
+        } else if selection.p0().1 >= MARGIN + h {
+            selection.p0().1 - MARGIN - h
+        } else {
+            selection.p1().1.saturating_sub(MARGIN + h)
+        };
+        let x = selection
+            .p1()
+            .0
+            .saturating_sub(w)
+            .min(width.saturating_sub(w));
+        toolbar.origin = (x, y);
+
+        toolbar
+    }
+
+    fn size(&self) -> Pos2 {
+        let rows = BUTTONS.len().div_ceil(self.columns);
+        let extent = |count: usize| 2 * PADDING + count * BUTTON_SIZE + (count - 1) * GAP;
+
+        (extent(self.columns), extent(rows))
+    }
+
+    pub fn area(&self) -> Rect {
+        let (w, h) = self.size();
+
+        Rect::new(
+            self.origin,
+            (self.origin.0 + w, self.origin.1 + h),
+            self.canvas,
+        )
+    }
+
+    fn button_area(&self, index: usize) -> Rect {
+        let (row, column) = (index / self.columns, index % self.columns);
+        let x = self.origin.0 + PADDING + column * (BUTTON_SIZE + GAP);
+        let y = self.origin.1 + PADDING + row * (BUTTON_SIZE + GAP);
+
+        Rect::new((x, y), (x + BUTTON_SIZE, y + BUTTON_SIZE), self.canvas)
+    }
+
+    /// The button under `pos`, if any.
+    pub fn hit(&self, pos: Pos2) -> Option<Button> {
+        (0..BUTTONS.len())
+            .find(|i| {
+                let area = self.button_area(*i);
+                (area.p0().0..area.p1().0).contains(&pos.0)
+                    && (area.p0().1..area.p1().1).contains(&pos.1)
+            })
+            .map(|i| BUTTONS[i])
+    }
+
+    /// Draws the buttons, highlighting the one of the `active` tool. The color swatch shows
+    /// `color`.
+    pub fn draw(
+        &self,
+        canvas: &mut [u8],
+        active: Option<DrawMode>,
+        color: (u8, u8, u8, u8),
+        highlight: (u8, u8, u8, u8),
+    ) {
+        let width = self.canvas.0;
+        let area = self.area();
+        draw_rect_filled(
+            canvas,
+            area.p0().0,
+            area.p0().1,
+            area.p1().0,
+            area.p1().1,
+            width,
+            BACKGROUND,
+        );
+
+        for (i, button) in BUTTONS.into_iter().enumerate() {
+            let area = self.button_area(i);
+            if active.is_some_and(|mode| button == Button::Tool(mode)) {
+                draw_rect_filled(
+                    canvas,
+                    area.p0().0,
+                    area.p0().1,
+                    area.p1().0,
+                    area.p1().1,
+                    width,
+                    highlight,
+                );
+            }
+            draw_icon(canvas, width, button, area, color);
+        }
+    }
+}
+
+fn draw_icon(canvas: &mut [u8], width: usize, button: Button, area: Rect, color: (u8, u8, u8, u8)) {
+    let inner = area.inset(6);
+    let ((x0, y0), (x1, y1)) = (inner.p0(), inner.p1());
+    let center = ((x0 + x1) / 2, (y0 + y1) / 2);
+
+    let label = match button {
+        Button::Tool(DrawMode::Arrow | DrawMode::ArrowFilled) => {
+            draw_line_thick(canvas, x0, y1, x1, y0, width, 2, true, ICON_COLOR);
+            if button == Button::Tool(DrawMode::Arrow) {
+                let x = x1.saturating_sub(6);
+                draw_line_thick(canvas, x, y0, x1, y0, width, 2, true, ICON_COLOR);
+                draw_line_thick(canvas, x1, y0, x1, y0 + 6, width, 2, true, ICON_COLOR);
+            } else {
+                let tip = (x1 as f64 + 1., y0 as f64 - 1.);
+                draw_triangle_filled_aa(
+                    canvas,
+                    tip,
+                    (tip.0 - 8., tip.1),
+                    (tip.0, tip.1 + 8.),
+                    width,
+                    ICON_COLOR,
+                );
+            }
+            None
+        }
+        Button::Tool(DrawMode::Line) => {
+            draw_line_thick(canvas, x0, y1, x1, y0, width, 2, true, ICON_COLOR);
+            None
+        }
+        Button::Tool(DrawMode::RectBorder) => {
+            draw_rect_bordered(canvas, x0, y0, x1, y1, width, 2, false, ICON_COLOR);
+            None
+        }
+        Button::Tool(DrawMode::RectFilled) => {
+            draw_rect_filled(canvas, x0, y0, x1, y1, width, ICON_COLOR);
+            None
+        }
+        Button::Tool(DrawMode::Ellipse) => {
+            draw_ellipse_bordered(canvas, x0, y0, x1, y1, width, 2, true, ICON_COLOR);
+            None
+        }
+        Button::Color => {
+            draw_rect_filled(canvas, x0, y0, x1, y1, width, color);
+            draw_rect_bordered(canvas, x0, y0, x1, y1, width, 1, false, ICON_COLOR);
+            None
+        }
+        Button::Tool(DrawMode::Step) => Some("#"),
+        Button::Tool(DrawMode::Path) => Some("~"),
+        Button::Tool(DrawMode::Highlight) => Some("▃"),
+        Button::Tool(DrawMode::RectBlurred) => Some("░"),
+        Button::Tool(DrawMode::RectPixelated) => Some("▒"),
+        Button::Tool(DrawMode::RectSecure) => Some("█"),
+        Button::Tool(DrawMode::Text) => Some("T"),
+        Button::Tool(DrawMode::Picker) => Some("⊕"),
+        Button::Thinner => Some("−"),
+        Button::Thicker => Some("+"),
+        Button::Undo => Some("↩"),
+        Button::Copy => Some("◫"),
+        Button::Save => Some("⇓"),
+    };
+
+    if let Some(label) = label {
+        draw_text_centered(canvas, width, FONT_SIZE, label, center, ICON_COLOR);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{point::Point, Pos2};
+use crate::{
+    blend::{blend_pixel, BlendMode},
+    point::Point,
+    Pos2,
+};
 
 pub fn draw_triangle_filled(
     canvas: &mut [u8],
@@ -9,7 +13,7 @@ pub fn draw_triangle_filled(
     color: (u8, u8, u8, u8),
 ) {
     let mut vs = [a, b, c];
-    vs.sort_by(|a, b| a.1.cmp(&b.1));
+    vs.sort_by_key(|v| v.1);
 
     let total_height = vs[2].1 - vs[0].1;
     for i in 0..total_height {
@@ -85,16 +89,14 @@ pub fn draw_triangle_filled(
 
         let (a, b) = if a.x > b.x { (b, a) } else { (a, b) };
 
-        for j in a.x as usize..=b.x as usize {
-            let red = color.0;
-            let green = color.1;
-            let blue = color.2;
-            let alpha = color.3;
-
-            canvas[(vs[0].1 + i) * (width * 4) + (j * 4)] = red;
-            canvas[((vs[0].1 + i) * (width * 4) + (j * 4)) + 1] = green;
-            canvas[((vs[0].1 + i) * (width * 4) + (j * 4)) + 2] = blue;
-            canvas[((vs[0].1 + i) * (width * 4) + (j * 4)) + 3] = alpha;
+        for j in a.x as usize..=(b.x as usize).min(width.saturating_sub(1)) {
+            blend_pixel(
+                canvas,
+                (vs[0].1 + i) * (width * 4) + (j * 4),
+                color,
+                1.,
+                BlendMode::SourceOver,
+            );
         }
     }
 }